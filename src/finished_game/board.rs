@@ -1,27 +1,134 @@
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Write;
 
 use colored::Colorize;
 
+use crate::finished_game::board::castling::CastlingRights;
+use crate::finished_game::board::history::UndoData;
 use crate::finished_game::color::Color;
+use crate::finished_game::engine;
+use crate::finished_game::error::ChessError;
+use crate::finished_game::language::Language;
 use crate::finished_game::piece::bishop::Bishop;
 use crate::finished_game::piece::king::{King, KING_NAME};
 use crate::finished_game::piece::knight::Knight;
 use crate::finished_game::piece::pawn::Pawn;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{create_piece, Piece, PieceKind};
 use crate::finished_game::piece::queen::Queen;
 use crate::finished_game::piece::rook::Rook;
-use crate::square::Square;
+use crate::finished_game::ruleset::Ruleset;
+use crate::square::{BOARD_SIZE, Square};
 
+mod ascii;
+mod attackers;
+pub mod castling;
+pub mod cells;
+mod chess960;
+mod diff;
+mod en_passant;
+mod fen;
+mod history;
+mod legality;
+mod pawn_structure;
+mod promotion;
+mod rays;
+mod result;
+mod san;
+mod see;
+mod state;
+mod zobrist;
+
+pub use result::GameResult;
+
+pub type LegalMoves = HashMap<(u8, u8), HashSet<(u8, u8)>>;
+
+/// A move from one square to another. `promotion` is the piece a pawn becomes on the last rank.
+/// Moves are made with `Board::create_move`, which fills in the `kind` from the position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Move {
+    pub from: (u8, u8),
+    pub to: (u8, u8),
+    pub promotion: Option<PieceKind>,
+    pub kind: MoveKind,
+}
+
+/// What a move does besides moving one piece, for the special moves that from and to squares alone
+/// do not tell apart
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MoveKind {
+    Quiet,
+    Capture,
+    /// The king moves two squares and the rook jumps over it
+    Castle,
+    /// A pawn takes a pawn that just made a double step, on the square it skipped
+    EnPassant,
+    /// A pawn moves two squares from its starting rank
+    DoublePush,
+}
+
+impl Move {
+    /// Returns the move in UCI notation, like `"e2e4"`, or `"a7a8n"` for a promotion to a knight
+    pub fn to_uci(self) -> String {
+        let promotion = self.promotion.map(|kind| create_piece(kind, Color::Black, self.to).symbol().to_string());
+        format!("{}{}{}", self.from.as_string(), self.to.as_string(), promotion.unwrap_or_default())
+    }
+}
+
+#[derive(Clone)]
 pub struct Board {
     pieces: HashMap<(u8, u8), Box<dyn Piece>>,
+    rules: Ruleset,
+    /// Language of the messages written while playing
+    language: Language,
+    active_color: Color,
+    castling_rights: CastlingRights,
+    /// The square skipped by a pawn double step on the last move
+    en_passant: Option<(u8, u8)>,
+    /// Half moves since the last capture or pawn move, for the fifty-move rule
+    halfmove_clock: u32,
+    /// Starts at 1 and goes up after every move by black
+    fullmove_number: u32,
+    /// `position_key` of every position so far, including the current one
+    position_history: Vec<String>,
+    /// Every move played, with what is needed to take it back
+    move_history: Vec<UndoData>,
+    /// The last result of `all_legal_moves`, with the zobrist hash and color it was computed for
+    legal_moves_cache: RefCell<Option<(u64, Color, LegalMoves)>>,
+}
+
+/// Boards are equal when they hold the same position: the same pieces on the same squares, the same
+/// color to move, castling rights and en passant square. The move counters and history are not compared.
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        let placement = |board: &Board| -> HashMap<(u8, u8), (Color, PieceKind)> {
+            board.pieces.iter().map(|(&square, piece)| (square, (piece.get_color(), piece.get_kind()))).collect()
+        };
+        self.active_color == other.active_color
+            && self.castling_rights == other.castling_rights
+            && self.en_passant == other.en_passant
+            && placement(self) == placement(other)
+    }
+}
+
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Board({})", self.to_fen())
+    }
 }
 
 impl Board {
     pub fn new() -> Board {
+        Board::new_with_rules(Ruleset::standard())
+    }
+
+    /// Creates a board in the starting position where move generation follows `rules`
+    pub fn new_with_rules(rules: Ruleset) -> Board {
         let mut pieces = Vec::<Box<dyn Piece>>::new();
         let teams: Vec<(Color, u8, u8)> = vec![(Color::White, 0, 1), (Color::Black, 7, 6)];
         for &(color, officer_rank, pawn_rank) in &teams {
-            for file in 0..=7 {
+            for file in 0..BOARD_SIZE {
                 pieces.push(Box::new(Pawn::new(color, (file, pawn_rank))));
             }
             pieces.push(Box::new(Rook::new(     color, (0, officer_rank))));
@@ -33,34 +140,249 @@ impl Board {
             pieces.push(Box::new(Knight::new(   color, (6, officer_rank))));
             pieces.push(Box::new(Rook::new(     color, (7, officer_rank))));
         }
-        Board {
-            pieces: pieces.into_iter().map(|piece| (*piece.get_position(), piece)).collect()
-        }
+        Board { rules, ..Board::from_pieces(pieces) }
+    }
+
+    /// Creates a board with only the given pieces, for setting up custom positions.
+    /// Kings and rooks on their starting squares keep the right to castle.
+    pub fn from_pieces(pieces: Vec<Box<dyn Piece>>) -> Board {
+        let mut board = Board {
+            pieces: pieces.into_iter().map(|piece| (*piece.get_position(), piece)).collect(),
+            rules: Ruleset::standard(),
+            language: Language::default(),
+            active_color: Color::White,
+            castling_rights: CastlingRights::none(),
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            position_history: Vec::new(),
+            move_history: Vec::new(),
+            legal_moves_cache: RefCell::new(None),
+        };
+        board.castling_rights = board.castling_rights_from_placement();
+        board.position_history.push(board.position_key());
+        board
+    }
+
+    fn get_piece_name(&self, position: &(u8, u8)) -> Result<String, ChessError> {
+        self.pieces.get(position).map(|piece| piece.name_in(self.language)).ok_or(ChessError::NoPiece(*position))
     }
 
-    fn get_piece_name(&self, position: &(u8, u8)) -> String {
-        self.pieces.get(position).map(|piece| piece.get_name()).unwrap()
+    /// Sets the language of piece names in messages. Norwegian is the default.
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
     }
 
     pub fn get_square_color(&self, position: &(u8, u8)) -> Option<Color> {
         self.pieces.get(position).map(|piece| piece.get_color())
     }
 
-    pub fn get_legal_squares(&self, position: &(u8, u8)) -> HashSet<(u8, u8)> {
-        let color = self.get_square_color(position).expect("Inga brikke på vald posisjon");
+    /// Returns the piece standing on `square`, if any
+    pub fn piece_at(&self, square: (u8, u8)) -> Option<&dyn Piece> {
+        self.pieces.get(&square).map(|piece| piece.as_ref())
+    }
+
+    /// Returns the kind of the piece standing on `square`, if any
+    pub fn kind_at(&self, square: (u8, u8)) -> Option<PieceKind> {
+        self.piece_at(square).map(|piece| piece.get_kind())
+    }
+
+    /// Returns the color of the piece standing on `square`, if any
+    pub fn color_at(&self, square: (u8, u8)) -> Option<Color> {
+        self.piece_at(square).map(|piece| piece.get_color())
+    }
+
+    /// Returns true if moving the piece at `from` to `to` takes a rival piece, en passant included,
+    /// without making the move
+    pub fn is_capture(&self, from: (u8, u8), to: (u8, u8)) -> bool {
+        let Some(piece) = self.piece_at(from) else { return false };
+        self.color_at(to) == Some(piece.get_color().opposite()) || self.en_passant_captured_square(&from, to).is_some()
+    }
+
+    /// Returns the squares the piece at `position` can legally move to
+    pub fn get_legal_squares(&self, position: &(u8, u8)) -> Result<HashSet<(u8, u8)>, ChessError> {
+        let piece = self.pieces.get(position).ok_or(ChessError::NoPiece(*position))?;
+        Ok(self.legal_squares_of(piece.as_ref()))
+    }
+
+    /// Returns the squares to highlight while the player drags the piece at `from`. Same as
+    /// `get_legal_squares`, but empty for an empty square or a piece of the color not to move,
+    /// so a UI can call it for any square that is clicked.
+    pub fn preview_moves(&self, from: (u8, u8)) -> HashSet<(u8, u8)> {
+        match self.color_at(from) == Some(self.active_color) {
+            true => self.get_legal_squares(&from).unwrap_or_default(),
+            false => HashSet::new(),
+        }
+    }
+
+    fn legal_squares_of(&self, piece: &dyn Piece) -> HashSet<(u8, u8)> {
+        let position = piece.get_position();
+        let color = piece.get_color();
         let team = self.get_positions(color);
         let rival_team = self.get_positions(color.opposite());
-        let piece = self.pieces.get(position).expect("Inga brikke på vald posisjon.");
-        let moves = piece.get_moves(&team, &rival_team);
-        moves
+        let moves = match piece.get_kind() {
+            PieceKind::King => self.king_safe_destinations(color).unwrap_or_default(),
+            _ => piece.get_moves(&team, &rival_team),
+        };
+        debug_assert!(moves.iter().all(Square::on_board), "{} på {} kan flytte utanfor brettet", piece.get_name(), position.as_string());
+        let mut legal_squares: HashSet<(u8, u8)> = moves
             .into_iter()
-            .filter(|&square| {
-                let mut new_board = Board {
-                    pieces: self.pieces.clone()
-                };
-                new_board.move_piece(&piece.get_position(), square);
-                !new_board.is_check(color)
-            }).collect()
+            .filter(|&square| !self.move_leaves_self_in_check(position, square))
+            .collect();
+        if piece.get_kind() == PieceKind::King {
+            legal_squares.extend(self.castling_targets(color));
+        }
+        if piece.get_kind() == PieceKind::Pawn {
+            legal_squares.extend(self.legal_en_passant(position));
+        }
+        legal_squares
+    }
+
+    /// Returns the squares the king of `color` can step to, leaving out the squares next to the rival king.
+    /// Castling and moves into the check of other pieces are not considered.
+    pub fn king_safe_destinations(&self, color: Color) -> Result<HashSet<(u8, u8)>, ChessError> {
+        let team = self.get_positions(color);
+        let rival_team = self.get_positions(color.opposite());
+        let rival_king = self.get_king_position(color.opposite()).ok();
+        Ok(self.pieces[self.get_king_position(color)?].get_moves(&team, &rival_team).into_iter()
+            .filter(|square| !square.neighbors().any(|neighbor| Some(&neighbor) == rival_king))
+            .collect())
+    }
+
+    /// Returns the legal squares of every piece of `color` that can move, keyed by the piece position.
+    /// With mandatory captures in the ruleset, only capturing moves are returned when there are any.
+    /// The result is cached until the board changes.
+    pub fn all_legal_moves(&self, color: Color) -> LegalMoves {
+        let hash = self.zobrist_hash();
+        if let Some((cached_hash, cached_color, legal_moves)) = &*self.legal_moves_cache.borrow() {
+            if *cached_hash == hash && *cached_color == color {
+                return legal_moves.clone();
+            }
+        }
+        let legal_moves = self.find_all_legal_moves(color);
+        *self.legal_moves_cache.borrow_mut() = Some((hash, color, legal_moves.clone()));
+        legal_moves
+    }
+
+    fn find_all_legal_moves(&self, color: Color) -> LegalMoves {
+        let all_moves: LegalMoves = self.get_pieces_iter(color)
+            .map(|piece| (*piece.get_position(), self.legal_squares_of(piece.as_ref())))
+            .filter(|(_, legal_squares)| !legal_squares.is_empty())
+            .collect();
+
+        let can_capture = all_moves.iter()
+            .any(|(&position, legal_squares)| legal_squares.iter().any(|&square| self.is_capture(position, square)));
+        if !(self.rules.mandatory_captures && can_capture) {
+            return all_moves;
+        }
+        all_moves.into_iter()
+            .map(|(position, legal_squares)| (position, legal_squares.into_iter().filter(|&square| self.is_capture(position, square)).collect::<HashSet<_>>()))
+            .filter(|(_, legal_squares)| !legal_squares.is_empty())
+            .collect()
+    }
+
+    /// Calls `f` with every legal move of `color`, one piece at a time, without building the map that
+    /// `all_legal_moves` returns. Like `legal_moves_with_promotions`, a pawn reaching the last rank is given
+    /// once for each piece it can be promoted to.
+    pub fn for_each_legal_move(&self, color: Color, mut f: impl FnMut(Move)) {
+        if self.rules.mandatory_captures {
+            for (from, legal_squares) in self.all_legal_moves(color) {
+                legal_squares.into_iter().flat_map(|to| self.moves_with_promotions(from, to)).for_each(&mut f);
+            }
+            return;
+        }
+        for piece in self.get_pieces_iter(color) {
+            let from = *piece.get_position();
+            self.legal_squares_of(piece.as_ref()).into_iter().flat_map(|to| self.moves_with_promotions(from, to)).for_each(&mut f);
+        }
+    }
+
+    /// Returns true if the king of specified color is under attack and has no way out
+    pub fn is_checkmate(&self, color: Color) -> bool {
+        self.is_check(color) && !self.has_legal_move(color)
+    }
+
+    /// Returns true if specified color has no legal moves without being in check
+    pub fn is_stalemate(&self, color: Color) -> bool {
+        !self.is_check(color) && !self.has_legal_move(color)
+    }
+
+    /// Returns true if `color` has at least one legal move. Stops at the first piece that can move.
+    pub fn has_legal_move(&self, color: Color) -> bool {
+        if let Some((cached_hash, cached_color, legal_moves)) = &*self.legal_moves_cache.borrow() {
+            if *cached_hash == self.zobrist_hash() && *cached_color == color {
+                return !legal_moves.is_empty();
+            }
+        }
+        self.get_pieces_iter(color).any(|piece| !self.legal_squares_of(piece.as_ref()).is_empty())
+    }
+
+    /// Returns the only legal move of `color`, or `None` if there are none or several
+    pub fn is_forced(&self, color: Color) -> Option<Move> {
+        let mut moves = self.legal_moves_with_promotions(color).into_iter();
+        match (moves.next(), moves.next()) {
+            (Some(only_move), None) => Some(only_move),
+            _ => None,
+        }
+    }
+
+    /// Returns the total value of the pieces of `color`, counted in pawns
+    pub fn material(&self, color: Color) -> i32 {
+        self.get_pieces_iter(color).map(|piece| piece.value()).sum()
+    }
+
+    /// Returns `engine::evaluate` from the point of view of `color`, positive when `color` is better
+    pub fn side_relative_eval(&self, color: Color) -> i32 {
+        match color {
+            Color::White => engine::evaluate(self),
+            Color::Black => -engine::evaluate(self),
+        }
+    }
+
+    /// Returns the number of white pieces minus the number of black pieces, per kind of piece
+    pub fn imbalance(&self) -> HashMap<PieceKind, i32> {
+        let mut imbalance = HashMap::new();
+        for piece in self.pieces.values() {
+            *imbalance.entry(piece.get_kind()).or_insert(0) += match piece.get_color() {
+                Color::White => 1,
+                Color::Black => -1,
+            };
+        }
+        imbalance
+    }
+
+    /// Returns the number of legal moves of `color`, summed per kind of piece
+    pub fn mobility_by_kind(&self, color: Color) -> HashMap<PieceKind, usize> {
+        let legal_moves = self.all_legal_moves(color);
+        let mut mobility = HashMap::new();
+        for piece in self.get_pieces_iter(color) {
+            let moves = legal_moves.get(piece.get_position()).map_or(0, |legal_squares| legal_squares.len());
+            *mobility.entry(piece.get_kind()).or_insert(0) += moves;
+        }
+        mobility
+    }
+
+    /// Returns the number of legal moves of `color`
+    pub fn mobility(&self, color: Color) -> usize {
+        self.mobility_by_kind(color).values().sum()
+    }
+
+    /// Returns the number of legal moves of the piece at `position`, or 0 for an empty square
+    pub fn piece_mobility(&self, position: &(u8, u8)) -> usize {
+        self.get_legal_squares(position).map_or(0, |legal_squares| legal_squares.len())
+    }
+
+    /// Returns the mobility of white minus the mobility of black, a cheap measure of who has the initiative
+    pub fn tempo(&self) -> i32 {
+        self.mobility(Color::White) as i32 - self.mobility(Color::Black) as i32
+    }
+
+    /// Returns every square a piece of `kind` and `color` can legally move to
+    pub fn destinations_for_kind(&self, color: Color, kind: PieceKind) -> HashSet<(u8, u8)> {
+        self.all_legal_moves(color).into_iter()
+            .filter(|(position, _)| self.kind_at(*position) == Some(kind))
+            .flat_map(|(_, legal_squares)| legal_squares)
+            .collect()
     }
 
     fn create_board(&self) -> Vec<Vec<char>> {
@@ -71,22 +393,171 @@ impl Board {
         board
     }
 
-    /// Move piece at `position` to square with position `target_square`
+    /// Returns the move of the piece at `from` to `to`, with its kind read from this position
+    pub fn create_move(&self, from: (u8, u8), to: (u8, u8), promotion: Option<PieceKind>) -> Move {
+        Move { from, to, promotion, kind: self.move_kind(&from, to) }
+    }
+
+    /// Reads a move written by `Move::to_uci` and played on this position
+    pub fn move_from_uci(&self, uci: &str) -> Option<Move> {
+        let promotion = match uci.get(4..)? {
+            "" => None,
+            "q" => Some(PieceKind::Queen),
+            "r" => Some(PieceKind::Rook),
+            "b" => Some(PieceKind::Bishop),
+            "n" => Some(PieceKind::Knight),
+            _ => return None,
+        };
+        Some(self.create_move(uci.get(..2)?.as_u8()?, uci.get(2..4)?.as_u8()?, promotion))
+    }
+
+    /// Returns what kind of move it is to move the piece at `position` to `target_square`
+    fn move_kind(&self, position: &(u8, u8), target_square: (u8, u8)) -> MoveKind {
+        let kind = self.pieces.get(position).map(|piece| piece.get_kind());
+        if self.castling_rook(*position, target_square).is_some() {
+            MoveKind::Castle
+        } else if self.en_passant_captured_square(position, target_square).is_some() {
+            MoveKind::EnPassant
+        } else if self.pieces.contains_key(&target_square) {
+            MoveKind::Capture
+        } else if kind == Some(PieceKind::Pawn) && position.1.abs_diff(target_square.1) == 2 {
+            MoveKind::DoublePush
+        } else {
+            MoveKind::Quiet
+        }
+    }
+
+    /// Move piece at `position` to square with position `target_square`. Nothing happens if there is
+    /// no piece at `position`; use `apply_move` to find out.
     pub fn move_piece(&mut self, position: &(u8, u8), target_square: (u8, u8)) {
-        let mut moving_piece = self.pieces.remove(position).unwrap();
+        let _ = self.apply_move(position, target_square);
+    }
+
+    /// Move piece at `position` to square with position `target_square`, returning the captured piece if any.
+    /// Afterwards it is the other color's turn. A pawn reaching the last rank becomes a queen.
+    pub fn apply_move(&mut self, position: &(u8, u8), target_square: (u8, u8)) -> Result<Option<Box<dyn Piece>>, ChessError> {
+        self.apply_move_with_promotion(position, target_square, PieceKind::Queen)
+    }
+
+    /// Same as `apply_move`, but a pawn reaching the last rank becomes a piece of kind `promotion`
+    pub fn apply_move_with_promotion(&mut self, position: &(u8, u8), target_square: (u8, u8), promotion: PieceKind) -> Result<Option<Box<dyn Piece>>, ChessError> {
+        let is_promotion = self.is_promotion(position, target_square);
+        let mv = self.create_move(*position, target_square, is_promotion.then_some(promotion));
+        let en_passant_captured_square = self.en_passant_captured_square(position, target_square);
+        let rook_move = self.castling_rook(*position, target_square);
+        let mut moving_piece = self.pieces.remove(position).ok_or(ChessError::NoPiece(*position))?;
+        self.legal_moves_cache.get_mut().take();
+        let piece_before_move = moving_piece.clone();
         moving_piece.move_piece(target_square);
-        self.pieces.remove(&target_square);
-        self.pieces.insert(target_square, moving_piece);
+        let is_pawn_move = moving_piece.get_kind() == PieceKind::Pawn;
+        if let Some((mut rook, rook_target)) = rook_move.and_then(|(rook_position, rook_target)| Some((self.pieces.remove(&rook_position)?, rook_target))) {
+            rook.move_piece(rook_target);
+            self.pieces.insert(rook_target, rook);
+        }
+        if is_promotion {
+            moving_piece = create_piece(promotion, moving_piece.get_color(), target_square);
+        }
+        self.active_color = moving_piece.get_color().opposite();
+        if moving_piece.get_color() == Color::Black {
+            self.fullmove_number += 1;
+        }
+        let captured_piece = match en_passant_captured_square {
+            Some(captured_square) => self.pieces.remove(&captured_square),
+            None => None,
+        };
+        let captured_piece = self.pieces.insert(target_square, moving_piece).or(captured_piece);
+
+        self.move_history.push(UndoData {
+            mv,
+            moved_piece: piece_before_move,
+            captured_piece: captured_piece.clone(),
+            halfmove_clock: self.halfmove_clock,
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+        });
+        self.update_castling_rights(*position, target_square);
+        self.en_passant = match is_pawn_move && position.1.abs_diff(target_square.1) == 2 {
+            true => Some((position.0, (position.1 + target_square.1) / 2)),
+            false => None,
+        };
+        self.halfmove_clock = match is_pawn_move || captured_piece.is_some() {
+            true => 0,
+            false => self.halfmove_clock + 1,
+        };
+        self.position_history.push(self.position_key());
+        Ok(captured_piece)
+    }
+
+    /// Returns the color whose turn it is
+    pub fn get_active_color(&self) -> Color {
+        self.active_color
+    }
+
+    /// Returns true if moving the piece at `position` to `target_square` takes a pawn to the last rank
+    pub fn is_promotion(&self, position: &(u8, u8), target_square: (u8, u8)) -> bool {
+        match self.pieces.get(position) {
+            Some(piece) if piece.get_kind() == PieceKind::Pawn => match piece.get_color() {
+                Color::White => target_square.1 == 7,
+                Color::Black => target_square.1 == 0,
+            },
+            _ => false,
+        }
+    }
+
+    pub fn capture(&mut self, position: &(u8, u8), target_square: (u8, u8), promotion: PieceKind) -> Result<(), ChessError> {
+        println!("{}", self.capture_message(position, target_square)?);
+        self.apply_move_with_promotion(position, target_square, promotion)?;
+        Ok(())
+    }
+
+    /// Returns a sentence describing the move of the piece at `position` to `target_square` in the board
+    /// language, for example `"Kvit dronning flyttar frå d1 til h5"`
+    pub fn describe_move(&self, position: &(u8, u8), target_square: (u8, u8)) -> Result<String, ChessError> {
+        let piece = self.pieces.get(position).ok_or(ChessError::NoPiece(*position))?;
+        let (name, from, to) = (piece.name_in(self.language), position.as_string(), target_square.as_string());
+        Ok(match self.language {
+            Language::Norwegian => format!("{} {} flyttar frå {} til {}", piece.get_color().print_capitalised(), name, from, to),
+            Language::English => {
+                let color = match piece.get_color() {
+                    Color::White => "White",
+                    Color::Black => "Black",
+                };
+                format!("{} {} moves from {} to {}", color, name, from, to)
+            }
+        })
     }
 
-    pub fn capture(&mut self, position: &(u8, u8), target_square: (u8, u8)) {
-        println!("{} fra {} fangar {} på {}", self.get_piece_name(position), position.as_string(), self.get_piece_name(&target_square), target_square.as_string());
-        self.move_piece(position, target_square);
+    fn capture_message(&self, position: &(u8, u8), target_square: (u8, u8)) -> Result<String, ChessError> {
+        let (piece, from, captured, to) = (self.get_piece_name(position)?, position.as_string(), self.get_piece_name(&target_square)?, target_square.as_string());
+        Ok(match self.language {
+            Language::Norwegian => format!("{} fra {} fangar {} på {}", piece, from, captured, to),
+            Language::English => format!("{} from {} captures {} on {}", piece, from, captured, to),
+        })
     }
 
-    /// Returns true if the king of specified color is under attack
+    /// Returns true if moving the piece at `position` to `target_square` puts the opponent in check
+    pub fn move_gives_check(&self, position: &(u8, u8), target_square: (u8, u8)) -> Result<bool, ChessError> {
+        self.promotion_gives_check(position, target_square, PieceKind::Queen)
+    }
+
+    /// Same as `move_gives_check`, but a pawn reaching the last rank becomes a piece of kind `promotion`
+    fn promotion_gives_check(&self, position: &(u8, u8), target_square: (u8, u8), promotion: PieceKind) -> Result<bool, ChessError> {
+        let color = self.color_at(*position).ok_or(ChessError::NoPiece(*position))?;
+        let mut new_board = self.clone();
+        new_board.apply_move_with_promotion(position, target_square, promotion)?;
+        Ok(new_board.is_check(color.opposite()))
+    }
+
+    /// Returns the legal moves of `color` that put the opponent in check, sorted
+    pub fn checking_moves(&self, color: Color) -> Vec<Move> {
+        self.legal_moves_with_promotions(color).into_iter()
+            .filter(|mv| self.promotion_gives_check(&mv.from, mv.to, mv.promotion.unwrap_or(PieceKind::Queen)) == Ok(true))
+            .collect()
+    }
+
+    /// Returns true if the king of specified color is under attack. A color without a king is never in check.
     pub fn is_check(&self, color: Color) -> bool {
-        let king_position = self.get_king_position(color);
+        let Ok(king_position) = self.get_king_position(color) else { return false };
         let team = self.get_positions(color);
         let rival_team = self.get_positions(color.opposite());
 
@@ -98,13 +569,46 @@ impl Board {
         false
     }
 
-    fn get_king_position(&self, color: Color) -> &(u8, u8) {
-        self.pieces.values().find(|piece| {
-            piece.get_color() == color && piece.get_name() == KING_NAME
-        }).unwrap().get_position()
+    /// Returns true if moving the piece at `position` to `target_square` would leave its own king in check.
+    /// Gives the same answer as making the move and calling `is_check`, but only looks at rival pieces
+    /// sharing a line (or a knight jump) with the king instead of every rival piece.
+    /// Returns false if there is no piece at `position` or no king to leave in check.
+    pub fn move_leaves_self_in_check(&self, position: &(u8, u8), target_square: (u8, u8)) -> bool {
+        let Some(piece) = self.pieces.get(position) else { return false };
+        let color = piece.get_color();
+        let king_position = match piece.get_name() == KING_NAME {
+            true => target_square,
+            false => match self.get_king_position(color) {
+                Ok(king_position) => *king_position,
+                Err(_) => return false,
+            },
+        };
+
+        let mut team = self.get_positions(color);
+        team.remove(position);
+        team.insert(target_square);
+        let mut rival_team = self.get_positions(color.opposite());
+        rival_team.remove(&target_square);
+
+        self.get_pieces_iter(color.opposite())
+            .filter(|rival| *rival.get_position() != target_square)
+            .filter(|rival| {
+                let (x, y) = *rival.get_position();
+                let dx = x.abs_diff(king_position.0);
+                let dy = y.abs_diff(king_position.1);
+                dx == 0 || dy == 0 || dx == dy || (dx.min(dy), dx.max(dy)) == (1, 2)
+            })
+            .any(|rival| rival.get_moves(&rival_team, &team).contains(&king_position))
+    }
+
+    fn get_king_position(&self, color: Color) -> Result<&(u8, u8), ChessError> {
+        self.pieces.values()
+            .find(|piece| piece.get_color() == color && piece.get_name() == KING_NAME)
+            .map(|king| king.get_position())
+            .ok_or(ChessError::MissingKing(color))
     }
 
-    fn get_positions(&self, color: Color) -> HashSet<(u8, u8)> {
+    pub(crate) fn get_positions(&self, color: Color) -> HashSet<(u8, u8)> {
         self.pieces.iter()
             .filter_map(|(&position, piece)| if piece.get_color() == color { Some(position) } else { None })
             .collect()
@@ -117,40 +621,78 @@ impl Board {
     pub fn get_checked_king(&self) -> Option<&(u8, u8)> {
         for color in [Color::White, Color::Black] {
             if self.is_check(color) {
-                return Some(self.get_king_position(color))
+                return self.get_king_position(color).ok()
             }
         }
         None
     }
 
     pub fn print(&self, legal_squares: Option<&HashSet<(u8, u8)>>) {
+        print!("{}", self.render(legal_squares));
+    }
+
+    /// Returns the board as printed by `print`, with `legal_squares` highlighted
+    pub fn render(&self, legal_squares: Option<&HashSet<(u8, u8)>>) -> String {
         let board = self.create_board();
         let empty_hashset = HashSet::new();
         let legal_squares = legal_squares.unwrap_or(&empty_hashset);
         let checked_king = self.get_checked_king();
 
-        println!("   {:_<33}", "");
+        let mut output = String::new();
+        writeln!(output, "   {:_<33}", "").unwrap();
         for (y, row) in board.iter().rev().enumerate() {
-            print!("{}  ", 8 - y);
+            write!(output, "{}  ", 8 - y).unwrap();
             for (x, piece) in row.iter().enumerate() {
                 match *piece {
-                    '_' if legal_squares.contains(&(x as u8, 7 - y as u8)) => print!("| {} ", "□".green()),
-                    '_' => print!("|   "),
-                    c if checked_king == Some(&(x as u8, 7 - y as u8)) => print!("| {} ", c.to_string().red()),
-                    c if legal_squares.contains(&(x as u8, 7 - y as u8)) => print!("| {} ", c.to_string().red()),
-                    c => print!("| {} ", c)
-                }
+                    '_' if legal_squares.contains(&(x as u8, 7 - y as u8)) => write!(output, "| {} ", "□".green()),
+                    '_' => write!(output, "|   "),
+                    c if checked_king == Some(&(x as u8, 7 - y as u8)) => write!(output, "| {} ", c.to_string().red()),
+                    c if legal_squares.contains(&(x as u8, 7 - y as u8)) => write!(output, "| {} ", c.to_string().red()),
+                    c => write!(output, "| {} ", c)
+                }.unwrap();
+            }
+            writeln!(output, "|").unwrap();
+        }
+        writeln!(output, "   {:͞<33}", "").unwrap(); // \u{035E}
+        writeln!(output, "     A   B   C   D   E   F   G   H").unwrap();
+        output
+    }
+
+    /// Prints the board with the internal `(x, y)` position written next to each piece
+    pub fn print_with_indices(&self) {
+        print!("{}", self.render_with_indices());
+    }
+
+    /// Returns the board as printed by `print_with_indices`
+    pub fn render_with_indices(&self) -> String {
+        let board = self.create_board();
+        let mut output = String::new();
+        writeln!(output, "   {:_<81}", "").unwrap();
+        for (y, row) in board.iter().enumerate().rev() {
+            write!(output, "{}  ", y + 1).unwrap();
+            for (x, piece) in row.iter().enumerate() {
+                let piece = if *piece == '_' { ' ' } else { *piece };
+                write!(output, "| {} ({},{}) ", piece, x, y).unwrap();
             }
-            println!("|")
+            writeln!(output, "|").unwrap();
         }
-        println!("   {:͞<33}", ""); // \u{035E}
-        println!("     A   B   C   D   E   F   G   H");
+        writeln!(output, "   {:͞<81}", "").unwrap();
+        writeln!(output, "     A         B         C         D         E         F         G         H").unwrap();
+        output
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use crate::finished_game::board::Board;
+    use crate::finished_game::color::Color;
+    use crate::finished_game::engine;
+    use crate::finished_game::error::ChessError;
+    use crate::finished_game::language::Language;
+    use crate::finished_game::piece::{create_piece, PieceKind};
+    use crate::finished_game::ruleset::Ruleset;
     use crate::set;
     use crate::square::{Square, Squares};
 
@@ -160,6 +702,29 @@ mod tests {
             let target = target.as_u8().unwrap();
             self.move_piece(&position, target);
         }
+
+        /// Panics if a piece of `color` can move to a square outside the board, ignoring checks
+        pub fn assert_no_offboard_moves(&self, color: Color) {
+            let team = self.get_positions(color);
+            let rival_team = self.get_positions(color.opposite());
+            for piece in self.get_pieces_iter(color) {
+                for square in piece.get_moves(&team, &rival_team) {
+                    assert!(square.on_board(), "{} på {:?} kan flytte til {:?}", piece.get_name(), piece.get_position(), square);
+                }
+            }
+        }
+
+        /// Panics if a piece is stored under another square than its own position, or if a color
+        /// does not have exactly one king
+        pub fn assert_consistent(&self) {
+            for (square, piece) in &self.pieces {
+                assert_eq!(square, piece.get_position(), "{} på {:?} er lagra på {:?}", piece.get_name(), piece.get_position(), square);
+            }
+            for color in [Color::White, Color::Black] {
+                let kings = self.get_pieces_iter(color).filter(|piece| piece.get_kind() == PieceKind::King).count();
+                assert_eq!(kings, 1, "{} har {} kongar", color, kings);
+            }
+        }
     }
 
     #[test]
@@ -168,7 +733,7 @@ mod tests {
         board.do_move("f7", "f5");
         board.do_move("d1", "h5");
         let legal_moves = set!["g6"];
-        assert_eq!(board.get_legal_squares(&"g7".as_u8().unwrap()), legal_moves)
+        assert_eq!(board.get_legal_squares(&"g7".as_u8().unwrap()).unwrap(), legal_moves)
     }
 
     #[test]
@@ -178,14 +743,322 @@ mod tests {
         board.do_move("d1", "h5");
         board.do_move("g7", "g6");
         let legal_moves = set!["h5"];
-        assert_eq!(board.get_legal_squares(&"g6".as_u8().unwrap()), legal_moves)
+        assert_eq!(board.get_legal_squares(&"g6".as_u8().unwrap()).unwrap(), legal_moves)
+    }
+
+    #[test]
+    fn incremental_check_matches_full_recompute_for_pinned_pawn() {
+        let mut board = Board::new();
+        board.do_move("f7", "f5");
+        board.do_move("d1", "h5");
+        board.do_move("g7", "g6");
+        let position = "g6".as_u8().unwrap();
+        let team = board.get_positions(Color::Black);
+        let rival_team = board.get_positions(Color::White);
+        for square in board.pieces[&position].get_moves(&team, &rival_team) {
+            let mut new_board = board.clone();
+            new_board.move_piece(&position, square);
+            assert_eq!(board.move_leaves_self_in_check(&position, square), new_board.is_check(Color::Black));
+        }
+    }
+
+    #[test]
+    fn antichess_captures_are_mandatory() {
+        let mut board = Board::new_with_rules(Ruleset::antichess());
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        let legal_moves = board.all_legal_moves(Color::White);
+        assert_eq!(legal_moves.len(), 1);
+        assert_eq!(legal_moves[&"e4".as_u8().unwrap()], set!["d5"])
+    }
+
+    #[test]
+    fn antichess_en_passant_is_a_mandatory_capture() {
+        let mut board = Board::new_with_rules(Ruleset::antichess());
+        for (position, target) in [("e2", "e4"), ("h7", "h6"), ("e4", "e5"), ("d7", "d5")] {
+            board.do_move(position, target);
+        }
+        let legal_moves = board.all_legal_moves(Color::White);
+        assert_eq!(legal_moves.len(), 1);
+        assert_eq!(legal_moves[&"e5".as_u8().unwrap()], set!["d6"])
+    }
+
+    #[test]
+    fn pawn_on_last_rank_is_promoted() {
+        let mut board = Board::from_ascii("
+            ........
+            P......k
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        assert!(board.is_promotion(&"a7".as_u8().unwrap(), "a8".as_u8().unwrap()));
+        assert!(!board.is_promotion(&"a1".as_u8().unwrap(), "a2".as_u8().unwrap()));
+        board.do_move("a7", "a8");
+        assert_eq!(board.pieces[&"a8".as_u8().unwrap()].get_kind(), PieceKind::Queen);
+    }
+
+    #[test]
+    fn apply_move_returns_captured_piece() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        let captured = board.apply_move(&"e4".as_u8().unwrap(), "d5".as_u8().unwrap()).unwrap().unwrap();
+        assert_eq!(captured.get_kind(), PieceKind::Pawn);
+        assert_eq!(captured.get_color(), Color::Black);
+        assert!(board.apply_move(&"d5".as_u8().unwrap(), "d6".as_u8().unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn same_position_by_different_move_orders_is_equal() {
+        let mut board = Board::new();
+        let mut other = Board::new();
+        for (position, target) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")] {
+            board.do_move(position, target);
+        }
+        for (position, target) in [("g1", "f3"), ("e7", "e5"), ("e2", "e4"), ("b8", "c6")] {
+            other.do_move(position, target);
+        }
+        assert_eq!(board, other);
+        other.do_move("f1", "c4");
+        assert_ne!(board, other);
+    }
+
+    #[test]
+    fn queen_move_is_described_in_norwegian() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("e7", "e5");
+        let (position, target_square) = ("d1".as_u8().unwrap(), "h5".as_u8().unwrap());
+        assert_eq!(board.describe_move(&position, target_square).unwrap(), "Kvit dronning flyttar frå d1 til h5");
+        board.set_language(Language::English);
+        assert_eq!(board.describe_move(&position, target_square).unwrap(), "White queen moves from d1 to h5");
+    }
+
+    #[test]
+    fn capture_message_in_english() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        let (position, target_square) = ("e4".as_u8().unwrap(), "d5".as_u8().unwrap());
+        assert_eq!(board.capture_message(&position, target_square).unwrap(), "bonde fra e4 fangar bonde på d5");
+        board.set_language(Language::English);
+        assert_eq!(board.capture_message(&position, target_square).unwrap(), "pawn from e4 captures pawn on d5");
+    }
+
+    #[test]
+    fn has_legal_move_in_start_and_stalemate_positions() {
+        assert!(Board::new().has_legal_move(Color::White));
+        let stalemate = Board::from_ascii("
+            k.......
+            ..Q.....
+            .K......
+            ........
+            ........
+            ........
+            ........
+            ........
+        ").unwrap();
+        assert!(!stalemate.has_legal_move(Color::Black));
+        assert!(stalemate.is_stalemate(Color::Black));
+    }
+
+    #[test]
+    fn king_with_single_escape_is_forced() {
+        let board = Board::from_ascii("
+            ....k..r
+            ........
+            ........
+            ........
+            ........
+            ........
+            r.......
+            .......K
+        ").unwrap();
+        assert_eq!(board.is_forced(Color::White), board.move_from_uci("h1g1"));
+        assert_eq!(Board::new().is_forced(Color::White), None);
+    }
+
+    #[test]
+    fn piece_at_start_position() {
+        let board = Board::new();
+        let king = board.piece_at((4, 0)).unwrap();
+        assert_eq!(king.get_kind(), PieceKind::King);
+        assert_eq!(king.get_color(), Color::White);
+        assert_eq!(board.kind_at("d8".as_u8().unwrap()), Some(PieceKind::Queen));
+        assert_eq!(board.color_at("d8".as_u8().unwrap()), Some(Color::Black));
+        assert!(board.piece_at((0, 4)).is_none());
+    }
+
+    #[test]
+    fn queen_to_h5_gives_check() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("f7", "f5");
+        assert_eq!(board.move_gives_check(&"d1".as_u8().unwrap(), "h5".as_u8().unwrap()), Ok(true));
+        assert_eq!(board.move_gives_check(&"d1".as_u8().unwrap(), "g4".as_u8().unwrap()), Ok(false));
+    }
+
+    #[test]
+    fn pieces_in_corners_stay_on_board() {
+        for kind in [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen, PieceKind::King] {
+            for color in [Color::White, Color::Black] {
+                for corner in ["a1", "h8"] {
+                    let board = Board::from_pieces(vec![
+                        create_piece(kind, color, corner.as_u8().unwrap()),
+                        create_piece(PieceKind::King, color, "e4".as_u8().unwrap()),
+                        create_piece(PieceKind::King, color.opposite(), "c6".as_u8().unwrap()),
+                    ]);
+                    board.assert_no_offboard_moves(color);
+                    assert!(board.get_legal_squares(&corner.as_u8().unwrap()).unwrap().iter().all(Square::on_board));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn side_relative_eval_is_negated_for_black() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        board.do_move("e4", "d5");
+        assert_eq!(board.side_relative_eval(Color::White), engine::evaluate(&board));
+        assert_eq!(board.side_relative_eval(Color::Black), -engine::evaluate(&board));
+        assert_eq!(board.side_relative_eval(Color::Black), -100);
+    }
+
+    #[test]
+    fn white_is_up_the_exchange() {
+        let board = Board::from_ascii("
+            ....k...
+            ..r..n..
+            ........
+            ........
+            ........
+            ........
+            ..R..R..
+            ....K...
+        ").unwrap();
+        let imbalance = board.imbalance();
+        assert_eq!(imbalance[&PieceKind::Rook], 1);
+        assert_eq!(imbalance[&PieceKind::Knight], -1);
+        assert_eq!(imbalance[&PieceKind::King], 0);
+    }
+
+    #[test]
+    fn knight_destinations_in_start_position() {
+        let board = Board::new();
+        assert_eq!(board.destinations_for_kind(Color::White, PieceKind::Knight), set!["a3", "c3", "f3", "h3"]);
+        assert!(board.destinations_for_kind(Color::White, PieceKind::Queen).is_empty());
+    }
+
+    #[test]
+    fn mobility_by_kind_in_start_position() {
+        let mobility = Board::new().mobility_by_kind(Color::White);
+        assert_eq!(mobility[&PieceKind::Knight], 4);
+        assert_eq!(mobility[&PieceKind::Bishop], 0);
+        assert_eq!(mobility[&PieceKind::Pawn], 16);
+    }
+
+    #[test]
+    fn tempo_favors_white_after_e4() {
+        let mut board = Board::new();
+        assert_eq!(board.tempo(), 0);
+        board.do_move("e2", "e4");
+        assert_eq!(board.mobility(Color::White), 30);
+        assert_eq!(board.tempo(), 10);
+    }
+
+    #[test]
+    fn knight_has_two_moves_and_rook_none_at_the_start() {
+        let board = Board::new();
+        assert_eq!(board.piece_mobility(&"g1".as_u8().unwrap()), 2);
+        assert_eq!(board.piece_mobility(&"a1".as_u8().unwrap()), 0);
+        assert_eq!(board.piece_mobility(&"e4".as_u8().unwrap()), 0);
+    }
+
+    #[test]
+    fn all_legal_moves_are_cached_until_board_changes() {
+        let mut board = Board::new();
+        let legal_moves = board.all_legal_moves(Color::White);
+        let hash = board.zobrist_hash();
+        assert!(matches!(&*board.legal_moves_cache.borrow(), Some((h, Color::White, _)) if *h == hash));
+        assert_eq!(board.all_legal_moves(Color::White), legal_moves);
+
+        board.do_move("e2", "e4");
+        assert!(board.legal_moves_cache.borrow().is_none());
+        assert_ne!(board.all_legal_moves(Color::White), legal_moves);
+    }
+
+    #[test]
+    fn preview_is_empty_for_empty_square_and_opponent() {
+        let board = Board::new();
+        assert_eq!(board.preview_moves("e4".as_u8().unwrap()), HashSet::new());
+        assert_eq!(board.preview_moves("e7".as_u8().unwrap()), HashSet::new());
+        assert_eq!(board.preview_moves("e2".as_u8().unwrap()), set!["e3", "e4"]);
+    }
+
+    #[test]
+    fn diagonal_pawn_move_onto_rival_is_capture() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        let square = |name: &str| name.as_u8().unwrap();
+        assert!(!board.is_capture(square("e4"), square("e5")));
+        assert!(board.is_capture(square("e4"), square("d5")));
+        assert!(!board.is_capture(square("e3"), square("d5")));
+
+        board.do_move("e4", "e5");
+        board.do_move("f7", "f5");
+        assert!(board.is_capture(square("e5"), square("f6")));
+    }
+
+    #[test]
+    fn visitor_gives_the_same_moves_as_all_legal_moves() {
+        let board = Board::new();
+        let mut visited = HashSet::new();
+        board.for_each_legal_move(Color::White, |mv| assert!(visited.insert((mv.from, mv.to))));
+        let expected: HashSet<((u8, u8), (u8, u8))> = board.all_legal_moves(Color::White).into_iter()
+            .flat_map(|(from, legal_squares)| legal_squares.into_iter().map(move |to| (from, to)))
+            .collect();
+        assert_eq!(visited.len(), 20);
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn visitor_gives_each_promotion() {
+        let board = Board::from_ascii("
+            .......k
+            P.......
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        let mut visited = Vec::new();
+        board.for_each_legal_move(Color::White, |mv| visited.push(mv));
+        visited.sort();
+        assert_eq!(visited, board.legal_moves_with_promotions(Color::White));
+        assert_eq!(visited.iter().filter(|mv| mv.promotion.is_some()).count(), 4);
+    }
+
+    #[test]
+    fn indices_overlay_shows_corner_positions() {
+        let overlay = Board::new().render_with_indices();
+        assert!(overlay.contains("♜ (0,0)"));
+        assert!(overlay.contains("♖ (7,7)"));
     }
 
     #[test]
     fn pawn_has_two_opening_moves() {
         let board = Board::new();
         let legal_moves = set!["e3", "e4"];
-        assert_eq!(board.get_legal_squares(&"e2".as_u8().unwrap()), legal_moves)
+        assert_eq!(board.get_legal_squares(&"e2".as_u8().unwrap()).unwrap(), legal_moves)
     }
 
     #[test]
@@ -193,6 +1066,81 @@ mod tests {
         let mut board = Board::new();
         board.do_move("a1", "d4");
         let legal_squares = set!["d3", "d5", "d6", "d7", "a4", "b4", "c4", "e4", "f4", "g4", "h4"];
-        assert_eq!(board.get_legal_squares(&"d4".as_u8().unwrap()), legal_squares)
+        assert_eq!(board.get_legal_squares(&"d4".as_u8().unwrap()).unwrap(), legal_squares)
+    }
+
+    #[test]
+    fn kings_can_not_step_next_to_each_other() {
+        let board = Board::from_ascii("
+            ........
+            ........
+            ........
+            ........
+            ...K.k..
+            ........
+            ........
+            ........
+        ").unwrap();
+        assert_eq!(board.king_safe_destinations(Color::White).unwrap(), set!["c3", "c4", "c5", "d3", "d5"]);
+        assert_eq!(board.king_safe_destinations(Color::Black).unwrap(), set!["f3", "f5", "g3", "g4", "g5"]);
+        assert_eq!(board.get_legal_squares(&"d4".as_u8().unwrap()).unwrap(), set!["c3", "c4", "c5", "d3", "d5"]);
+    }
+
+    #[test]
+    fn empty_square_and_missing_king_are_errors() {
+        let board = Board::from_ascii("
+            ........
+            ........
+            ........
+            ........
+            ...K....
+            ........
+            ........
+            ........
+        ").unwrap();
+        let empty = "e4".as_u8().unwrap();
+        assert_eq!(board.get_legal_squares(&empty), Err(ChessError::NoPiece(empty)));
+        let e5 = "e5".as_u8().unwrap();
+        assert_eq!(board.to_san(board.create_move(empty, e5, None)), Err(ChessError::NoPiece(empty)));
+        assert_eq!(board.clone().apply_move(&empty, e5).err(), Some(ChessError::NoPiece(empty)));
+        assert_eq!(board.move_gives_check(&empty, e5), Err(ChessError::NoPiece(empty)));
+        assert_eq!(board.see(&empty, e5), Err(ChessError::NoPiece(empty)));
+        assert_eq!(board.defended_squares(empty), Err(ChessError::NoPiece(empty)));
+        assert_eq!(board.move_rays(&empty), Err(ChessError::NoPiece(empty)));
+        assert_eq!(board.king_safe_destinations(Color::Black), Err(ChessError::MissingKing(Color::Black)));
+        assert!(!board.is_check(Color::Black));
+        assert_eq!(board.get_legal_squares(&"d4".as_u8().unwrap()).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn king_jump_without_rook_moves_only_the_king() {
+        let mut board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            ....K...
+        ").unwrap();
+        assert!(board.apply_move(&"e1".as_u8().unwrap(), "g1".as_u8().unwrap()).is_ok());
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/6K1 b - - 1 1");
+    }
+
+    #[test]
+    fn queen_has_two_checking_moves() {
+        let board = Board::from_ascii("
+            .......k
+            ......pp
+            ........
+            ........
+            ........
+            ........
+            ........
+            Q......K
+        ").unwrap();
+        let checks: Vec<String> = board.checking_moves(Color::White).iter().map(|mv| mv.to.as_string()).collect();
+        assert_eq!(checks, vec!["a8", "g7"]);
     }
 }
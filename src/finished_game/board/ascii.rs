@@ -0,0 +1,103 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::{create_piece, Piece, PieceKind};
+use crate::square::BOARD_SIZE;
+
+#[derive(Debug, PartialEq)]
+pub enum AsciiError {
+    /// The diagram does not have 8 rows
+    WrongRowCount(usize),
+    /// The row, counted from the top, does not have 8 squares
+    WrongRowLength(usize),
+    UnknownPiece(char),
+}
+
+fn piece_from_ascii(letter: char, position: (u8, u8)) -> Result<Box<dyn Piece>, AsciiError> {
+    let kind = match letter.to_ascii_lowercase() {
+        'p' => PieceKind::Pawn,
+        'n' => PieceKind::Knight,
+        'b' => PieceKind::Bishop,
+        'r' => PieceKind::Rook,
+        'q' => PieceKind::Queen,
+        'k' => PieceKind::King,
+        _ => return Err(AsciiError::UnknownPiece(letter)),
+    };
+    let color = if letter.is_ascii_uppercase() { Color::White } else { Color::Black };
+    Ok(create_piece(kind, color, position))
+}
+
+impl Board {
+    /// Returns the board as 8 rows of 8 chars, starting with rank 8. White pieces are upper case
+    /// letters, black pieces lower case and empty squares `.`
+    pub fn to_ascii(&self) -> String {
+        let mut ascii = String::new();
+        for y in (0..BOARD_SIZE).rev() {
+            for x in 0..BOARD_SIZE {
+                ascii.push(self.pieces.get(&(x, y)).map_or('.', |piece| piece.symbol()));
+            }
+            ascii.push('\n');
+        }
+        ascii
+    }
+
+    /// Creates a board from a diagram in the format of `to_ascii`, with white to move.
+    /// Whitespace around the diagram and each row is ignored.
+    pub fn from_ascii(ascii: &str) -> Result<Board, AsciiError> {
+        let rows: Vec<&str> = ascii.trim().lines().map(|row| row.trim()).collect();
+        if rows.len() != BOARD_SIZE as usize {
+            return Err(AsciiError::WrongRowCount(rows.len()));
+        }
+        let mut pieces = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            if row.chars().count() != BOARD_SIZE as usize {
+                return Err(AsciiError::WrongRowLength(i));
+            }
+            for (x, letter) in row.chars().enumerate() {
+                if letter != '.' {
+                    pieces.push(piece_from_ascii(letter, (x as u8, BOARD_SIZE - 1 - i as u8))?);
+                }
+            }
+        }
+        Ok(Board::from_pieces(pieces))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::ascii::AsciiError;
+    use crate::finished_game::board::Board;
+
+    const START_POSITION: &str = "\
+        rnbqkbnr
+        pppppppp
+        ........
+        ........
+        ........
+        ........
+        PPPPPPPP
+        RNBQKBNR
+    ";
+
+    #[test]
+    fn start_position_to_ascii() {
+        let expected = "rnbqkbnr\npppppppp\n........\n........\n........\n........\nPPPPPPPP\nRNBQKBNR\n";
+        assert_eq!(Board::new().to_ascii(), expected);
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("g8", "f6");
+        let ascii = board.to_ascii();
+        assert_eq!(Board::from_ascii(&ascii).unwrap().to_ascii(), ascii);
+        assert_eq!(Board::from_ascii(START_POSITION).unwrap().diff(&Board::new()), vec![]);
+    }
+
+    #[test]
+    fn invalid_diagrams_are_rejected() {
+        assert_eq!(Board::from_ascii("rnbqkbnr").err(), Some(AsciiError::WrongRowCount(1)));
+        assert_eq!(Board::from_ascii(&START_POSITION.replace("RNBQKBNR", "RNBQKBN")).err(), Some(AsciiError::WrongRowLength(7)));
+        assert_eq!(Board::from_ascii(&START_POSITION.replace("RNBQKBNR", "RNBQKBNX")).err(), Some(AsciiError::UnknownPiece('X')));
+    }
+}
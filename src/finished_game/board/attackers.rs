@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use crate::finished_game::board::{Board, Move};
+use crate::finished_game::color::Color;
+use crate::finished_game::error::ChessError;
+use crate::finished_game::piece::PieceKind;
+use crate::square::BOARD_SIZE;
+
+impl Board {
+    /// Returns the positions of all pieces of color `by` attacking `square`, sorted.
+    /// Pieces of the same color as the one standing on `square` are its defenders.
+    pub fn attackers_of(&self, square: (u8, u8), by: Color) -> Vec<(u8, u8)> {
+        let mut team = self.get_positions(by);
+        team.remove(&square);
+        let mut rival_team = self.get_positions(by.opposite());
+        rival_team.insert(square);
+        let mut attackers: Vec<(u8, u8)> = self.get_pieces_iter(by)
+            .filter(|piece| piece.get_moves(&team, &rival_team).contains(&square))
+            .map(|piece| *piece.get_position())
+            .collect();
+        attackers.sort();
+        attackers
+    }
+
+    /// Returns true if a piece of color `by` attacks `square`
+    pub fn is_square_attacked(&self, square: (u8, u8), by: Color) -> bool {
+        !self.attackers_of(square, by).is_empty()
+    }
+
+    /// Returns how many of the four center squares d4, d5, e4 and e5 `color` attacks
+    pub fn center_control(&self, color: Color) -> u32 {
+        [(3, 3), (3, 4), (4, 3), (4, 4)].into_iter()
+            .filter(|&square| self.is_square_attacked(square, color))
+            .count() as u32
+    }
+
+    /// Returns the squares of the friendly pieces the piece at `position` defends, that is where it would
+    /// recapture if a rival took the piece
+    pub fn defended_squares(&self, position: (u8, u8)) -> Result<HashSet<(u8, u8)>, ChessError> {
+        let color = self.color_at(position).ok_or(ChessError::NoPiece(position))?;
+        Ok(self.get_positions(color).into_iter()
+            .filter(|&square| square != position && self.attackers_of(square, color).contains(&position))
+            .collect())
+    }
+
+    /// Returns the legal moves of `color` after which the moved piece attacks two or more valuable rival
+    /// pieces at once, that is the king or pieces worth more than a pawn, together with the attacked squares.
+    /// Sorted by move.
+    pub fn fork_moves(&self, color: Color) -> Vec<(Move, Vec<(u8, u8)>)> {
+        self.legal_moves_with_promotions(color).into_iter()
+            .filter_map(|mv| {
+                let mut board = self.clone();
+                board.apply_move_with_promotion(&mv.from, mv.to, mv.promotion.unwrap_or(PieceKind::Queen)).ok()?;
+                let mut targets: Vec<(u8, u8)> = board.get_pieces_iter(color.opposite())
+                    .filter(|piece| piece.get_kind() == PieceKind::King || piece.value() > 1)
+                    .map(|piece| *piece.get_position())
+                    .filter(|&square| board.attackers_of(square, color).contains(&mv.to))
+                    .collect();
+                targets.sort();
+                (targets.len() >= 2).then_some((mv, targets))
+            })
+            .collect()
+    }
+
+    /// Returns how many pieces of color `by` attack or defend each square, indexed as `[rank][file]`
+    pub fn attack_map(&self, by: Color) -> [[u8; 8]; 8] {
+        let mut map = [[0; 8]; 8];
+        for (y, rank) in map.iter_mut().enumerate() {
+            for (x, attackers) in rank.iter_mut().enumerate() {
+                *attackers = self.attackers_of((x as u8, y as u8), by).len() as u8;
+            }
+        }
+        map
+    }
+
+    /// Returns the squares on the back rank of `color` where a rook or queen of the opponent can move
+    /// to and give checkmate, because the king is shut in by its own pieces. Sorted.
+    pub fn back_rank_mate_threats(&self, color: Color) -> Vec<(u8, u8)> {
+        let back_rank = match color {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        if !self.get_king_position(color).is_ok_and(|king| king.1 == back_rank) {
+            return Vec::new();
+        }
+        (0..BOARD_SIZE)
+            .map(|x| (x, back_rank))
+            .filter(|&square| {
+                self.attackers_of(square, color.opposite()).into_iter()
+                    .filter(|attacker| matches!(self.pieces[attacker].get_kind(), PieceKind::Rook | PieceKind::Queen))
+                    .filter(|attacker| self.get_legal_squares(attacker).is_ok_and(|legal_squares| legal_squares.contains(&square)))
+                    .any(|attacker| {
+                        let mut new_board = self.clone();
+                        new_board.apply_move(&attacker, square).is_ok() && new_board.is_checkmate(color)
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the legal moves of `color` where the moving piece gets out of the way of one of its own
+    /// bishops, rooks or queens, so that it attacks an enemy piece it did not attack before
+    pub fn discovered_attack_moves(&self, color: Color) -> Vec<Move> {
+        let sliders: Vec<(u8, u8)> = self.get_pieces_iter(color)
+            .filter(|piece| matches!(piece.get_kind(), PieceKind::Bishop | PieceKind::Rook | PieceKind::Queen))
+            .map(|piece| *piece.get_position())
+            .collect();
+        let attacked_by_sliders = |board: &Board, mover: (u8, u8)| -> Vec<((u8, u8), (u8, u8))> {
+            board.get_positions(color.opposite()).into_iter()
+                .flat_map(|target| board.attackers_of(target, color).into_iter().map(move |attacker| (attacker, target)))
+                .filter(|(attacker, _)| *attacker != mover && sliders.contains(attacker))
+                .collect()
+        };
+
+        self.legal_moves_with_promotions(color).into_iter()
+            .filter(|mv| {
+                let before = attacked_by_sliders(self, mv.from);
+                let mut new_board = self.clone();
+                new_board.apply_move_with_promotion(&mv.from, mv.to, mv.promotion.unwrap_or(PieceKind::Queen)).is_ok()
+                    && attacked_by_sliders(&new_board, mv.to).iter().any(|attack| !before.contains(attack))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{assert_eq_set, set};
+    use crate::finished_game::board::Board;
+    use crate::finished_game::color::Color;
+    use crate::square::{Square, Squares};
+
+    #[test]
+    fn f3_is_attacked_by_knight_and_pawns() {
+        let board = Board::new();
+        let mut expected = vec!["g1".as_u8().unwrap(), "e2".as_u8().unwrap(), "g2".as_u8().unwrap()];
+        expected.sort();
+        assert_eq!(board.attackers_of("f3".as_u8().unwrap(), Color::White), expected);
+        assert_eq!(board.attackers_of("f3".as_u8().unwrap(), Color::Black), vec![]);
+    }
+
+    #[test]
+    fn defenders_of_own_piece() {
+        let board = Board::new();
+        let mut expected = vec!["d1".as_u8().unwrap(), "e1".as_u8().unwrap(), "f1".as_u8().unwrap(), "g1".as_u8().unwrap()];
+        expected.sort();
+        assert_eq!(board.attackers_of("e2".as_u8().unwrap(), Color::White), expected);
+    }
+
+    #[test]
+    fn rook_defends_pawn_in_front_of_it() {
+        let board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            P.......
+            R...K...
+        ").unwrap();
+        assert_eq_set!(board.defended_squares("a1".as_u8().unwrap()).unwrap(), set!["a2", "e1"]);
+    }
+
+    #[test]
+    fn attack_map_of_start_position() {
+        let map = Board::new().attack_map(Color::White);
+        let count = |square: &str| {
+            let (x, y) = square.as_u8().unwrap();
+            map[y as usize][x as usize]
+        };
+        assert_eq!([count("c3"), count("d3"), count("e3"), count("f3")], [3, 2, 2, 3]);
+        assert_eq!([count("d4"), count("e4"), count("d5"), count("e5")], [0, 0, 0, 0]);
+        assert_eq!(count("e2"), 4);
+    }
+
+    #[test]
+    fn back_rank_mate_threat() {
+        let board = Board::from_ascii("
+            r..q...k
+            ........
+            ........
+            ........
+            ........
+            ........
+            .....PPP
+            ......K.
+        ").unwrap();
+        assert_eq!(board.back_rank_mate_threats(Color::White), vec!["a1".as_u8().unwrap(), "d1".as_u8().unwrap()]);
+        assert_eq!(Board::new().back_rank_mate_threats(Color::White), vec![]);
+    }
+
+    #[test]
+    fn knight_move_reveals_bishop_attack_on_queen() {
+        let board = Board::from_ascii("
+            k.......
+            ......q.
+            ........
+            ........
+            ...N....
+            ........
+            .B.....K
+            ........
+        ").unwrap();
+        let moves = board.discovered_attack_moves(Color::White);
+        assert_eq!(moves.len(), 8);
+        assert!(moves.iter().all(|mv| mv.from == "d4".as_u8().unwrap()));
+        assert!(moves.contains(&board.move_from_uci("d4f5").unwrap()));
+    }
+
+    #[test]
+    fn knight_forks_king_and_queen() {
+        let board = Board::from_ascii("
+            q...k...
+            ........
+            ........
+            .N......
+            ........
+            ........
+            ........
+            ....K...
+        ").unwrap();
+        let forks = board.fork_moves(Color::White);
+        assert_eq!(forks.len(), 1);
+        let (mv, targets) = &forks[0];
+        assert_eq!((mv.from.as_string(), mv.to.as_string()), (String::from("b5"), String::from("c7")));
+        assert_eq!(targets, &vec!["a8".as_u8().unwrap(), "e8".as_u8().unwrap()]);
+    }
+
+    #[test]
+    fn e4_controls_the_center() {
+        let mut board = Board::new();
+        assert_eq!(board.center_control(Color::White), 0);
+        board.do_move("e2", "e4");
+        assert!(board.is_square_attacked("d5".as_u8().unwrap(), Color::White));
+        assert!(board.center_control(Color::White) > board.center_control(Color::Black));
+    }
+}
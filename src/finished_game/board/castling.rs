@@ -0,0 +1,245 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+use crate::square::Square;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CastleSide {
+    KingSide,
+    QueenSide,
+}
+
+const CASTLE_SIDES: [CastleSide; 2] = [CastleSide::KingSide, CastleSide::QueenSide];
+
+/// Which castlings each color may still do, as long as the path is free and safe
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CastlingRights {
+    pub white_king_side: bool,
+    pub white_queen_side: bool,
+    pub black_king_side: bool,
+    pub black_queen_side: bool,
+}
+
+impl CastlingRights {
+    pub fn none() -> CastlingRights {
+        CastlingRights { white_king_side: false, white_queen_side: false, black_king_side: false, black_queen_side: false }
+    }
+
+    pub fn has(&self, color: Color, side: CastleSide) -> bool {
+        match (color, side) {
+            (Color::White, CastleSide::KingSide) => self.white_king_side,
+            (Color::White, CastleSide::QueenSide) => self.white_queen_side,
+            (Color::Black, CastleSide::KingSide) => self.black_king_side,
+            (Color::Black, CastleSide::QueenSide) => self.black_queen_side,
+        }
+    }
+
+    fn set(&mut self, color: Color, side: CastleSide, allowed: bool) {
+        match (color, side) {
+            (Color::White, CastleSide::KingSide) => self.white_king_side = allowed,
+            (Color::White, CastleSide::QueenSide) => self.white_queen_side = allowed,
+            (Color::Black, CastleSide::KingSide) => self.black_king_side = allowed,
+            (Color::Black, CastleSide::QueenSide) => self.black_queen_side = allowed,
+        }
+    }
+}
+
+fn king_home(color: Color) -> (u8, u8) {
+    match color {
+        Color::White => (4, 0),
+        Color::Black => (4, 7),
+    }
+}
+
+fn rook_home(color: Color, side: CastleSide) -> (u8, u8) {
+    let (_, rank) = king_home(color);
+    match side {
+        CastleSide::KingSide => (7, rank),
+        CastleSide::QueenSide => (0, rank),
+    }
+}
+
+/// The squares the king and the rook end up on after castling
+fn castled_squares(color: Color, side: CastleSide) -> ((u8, u8), (u8, u8)) {
+    let (_, rank) = king_home(color);
+    match side {
+        CastleSide::KingSide => ((6, rank), (5, rank)),
+        CastleSide::QueenSide => ((2, rank), (3, rank)),
+    }
+}
+
+/// Returns the move of the rook as (from, to) if the king moving from `king_from` to `king_to` is castling
+pub(crate) fn castling_rook_move(king_from: (u8, u8), king_to: (u8, u8)) -> Option<((u8, u8), (u8, u8))> {
+    [Color::White, Color::Black].into_iter()
+        .filter(|&color| king_home(color) == king_from)
+        .flat_map(|color| CASTLE_SIDES.map(|side| (color, side)))
+        .find(|&(color, side)| castled_squares(color, side).0 == king_to)
+        .map(|(color, side)| (rook_home(color, side), castled_squares(color, side).1))
+}
+
+impl Board {
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    /// Returns the squares the rook moves from and to when the king at `king_from` castles to `king_to`,
+    /// or `None` if the move is not a castling move: there is no king at `king_from`, no rook of its color
+    /// in the corner, or a piece stands where the rook would go
+    pub(crate) fn castling_rook(&self, king_from: (u8, u8), king_to: (u8, u8)) -> Option<((u8, u8), (u8, u8))> {
+        let color = self.pieces.get(&king_from).filter(|piece| piece.get_kind() == PieceKind::King)?.get_color();
+        castling_rook_move(king_from, king_to)
+            .filter(|&(rook_position, rook_target)| self.is_piece(rook_position, color, PieceKind::Rook) && !self.pieces.contains_key(&rook_target))
+    }
+
+    /// Returns true if `color` can castle on `side` now: the right is not lost, the squares between king
+    /// and rook are empty, the king is not in check and does not pass through or land on an attacked square
+    pub fn can_castle(&self, color: Color, side: CastleSide) -> bool {
+        let king = king_home(color);
+        let rook = rook_home(color, side);
+        let (king_target, _) = castled_squares(color, side);
+        self.rules.castling
+            && self.castling_rights.has(color, side)
+            && self.is_piece(king, color, PieceKind::King)
+            && self.is_piece(rook, color, PieceKind::Rook)
+            && king.squares_between(&rook).iter().all(|square| !self.pieces.contains_key(square))
+            && !self.is_check(color)
+            && king.squares_between(&king_target).into_iter().chain([king_target])
+                .all(|square| self.attackers_of(square, color.opposite()).is_empty())
+    }
+
+    /// Returns the squares the king of `color` can castle to
+    pub(crate) fn castling_targets(&self, color: Color) -> Vec<(u8, u8)> {
+        CASTLE_SIDES.into_iter()
+            .filter(|&side| self.can_castle(color, side))
+            .map(|side| castled_squares(color, side).0)
+            .collect()
+    }
+
+    /// Returns the rights of the kings and rooks that stand on their starting squares
+    pub(crate) fn castling_rights_from_placement(&self) -> CastlingRights {
+        let mut rights = CastlingRights::none();
+        for color in [Color::White, Color::Black] {
+            for side in CASTLE_SIDES {
+                let allowed = self.is_piece(king_home(color), color, PieceKind::King)
+                    && self.is_piece(rook_home(color, side), color, PieceKind::Rook);
+                rights.set(color, side, allowed);
+            }
+        }
+        rights
+    }
+
+    /// Removes the rights lost by a move from `position` to `target_square`: moving the king,
+    /// or moving or capturing a rook on its starting square
+    pub(crate) fn update_castling_rights(&mut self, position: (u8, u8), target_square: (u8, u8)) {
+        for color in [Color::White, Color::Black] {
+            for side in CASTLE_SIDES {
+                if [king_home(color), rook_home(color, side)].iter().any(|&square| square == position || square == target_square) {
+                    self.castling_rights.set(color, side, false);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn is_piece(&self, square: (u8, u8), color: Color, kind: PieceKind) -> bool {
+        self.pieces.get(&square).is_some_and(|piece| piece.get_color() == color && piece.get_kind() == kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::board::castling::CastleSide;
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn castle_is_blocked_by_piece_in_the_way() {
+        let board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            R...K.NR
+        ").unwrap();
+        assert!(!board.can_castle(Color::White, CastleSide::KingSide));
+        assert!(board.can_castle(Color::White, CastleSide::QueenSide));
+        assert!(!Board::new().can_castle(Color::White, CastleSide::KingSide));
+    }
+
+    #[test]
+    fn king_can_not_castle_through_check() {
+        let board = Board::from_ascii("
+            ....kr..
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            R...K..R
+        ").unwrap();
+        assert!(!board.can_castle(Color::White, CastleSide::KingSide));
+        assert!(board.can_castle(Color::White, CastleSide::QueenSide));
+
+        let in_check = Board::from_ascii("
+            k.......
+            ....r...
+            ........
+            ........
+            ........
+            ........
+            ........
+            R...K..R
+        ").unwrap();
+        assert!(!in_check.can_castle(Color::White, CastleSide::QueenSide));
+    }
+
+    #[test]
+    fn castle_moves_rook_and_is_taken_back() {
+        let mut board = Board::from_ascii("
+            r...k..r
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            R...K..R
+        ").unwrap();
+        assert!(board.get_legal_squares(&"e1".as_u8().unwrap()).unwrap().contains(&"g1".as_u8().unwrap()));
+        board.do_move("e1", "g1");
+        assert_eq!(board.kind_at("f1".as_u8().unwrap()), Some(PieceKind::Rook));
+        assert_eq!(board.kind_at("h1".as_u8().unwrap()), None);
+        assert!(!board.castling_rights().white_queen_side);
+        assert!(board.castling_rights().black_king_side);
+
+        board.undo_move();
+        assert_eq!(board.kind_at("h1".as_u8().unwrap()), Some(PieceKind::Rook));
+        assert_eq!(board.kind_at("e1".as_u8().unwrap()), Some(PieceKind::King));
+        assert!(board.castling_rights().white_queen_side);
+    }
+
+    #[test]
+    fn moving_rook_loses_its_right() {
+        let mut board = Board::from_ascii("
+            r...k..r
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            R...K..R
+        ").unwrap();
+        board.do_move("a1", "a2");
+        board.do_move("h8", "h1");
+        assert!(!board.can_castle(Color::White, CastleSide::QueenSide));
+        assert!(!board.castling_rights().white_king_side);
+        assert!(!board.castling_rights().black_king_side);
+        assert!(board.castling_rights().black_queen_side);
+    }
+}
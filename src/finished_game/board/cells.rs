@@ -0,0 +1,93 @@
+#![allow(dead_code, reason = "render_cells is for GUI and web frontends, the terminal game draws with render")]
+
+use std::collections::HashSet;
+
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+use crate::square::{BOARD_SIZE, Square};
+
+/// The color of a square. The a1 square is dark.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shade {
+    Light,
+    Dark,
+}
+
+/// Why a square is marked when drawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Highlight {
+    /// The selected piece can move here
+    LegalSquare,
+    /// The king on this square is in check
+    Check,
+}
+
+/// One square of the board as a frontend needs it, without any terminal colors or piece glyphs
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub square: (u8, u8),
+    pub shade: Shade,
+    pub piece: Option<(Color, PieceKind)>,
+    pub highlight: Option<Highlight>,
+}
+
+impl Board {
+    /// Returns the 64 squares in reading order from a8 to h1, marked the same way as `render` marks them
+    pub fn render_cells(&self, legal_squares: Option<&HashSet<(u8, u8)>>) -> Vec<Cell> {
+        let checked_king = self.get_checked_king().copied();
+        (0..BOARD_SIZE).rev()
+            .flat_map(|y| (0..BOARD_SIZE).map(move |x| (x, y)))
+            .map(|square| Cell {
+                square,
+                shade: if square.is_light() { Shade::Light } else { Shade::Dark },
+                piece: self.piece_at(square).map(|piece| (piece.get_color(), piece.get_kind())),
+                highlight: if checked_king == Some(square) {
+                    Some(Highlight::Check)
+                } else if legal_squares.is_some_and(|legal_squares| legal_squares.contains(&square)) {
+                    Some(Highlight::LegalSquare)
+                } else {
+                    None
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::board::cells::{Highlight, Shade};
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn start_position_cells() {
+        let cells = Board::new().render_cells(None);
+        assert_eq!(cells.len(), 64);
+        assert_eq!(cells.iter().filter(|cell| cell.piece.is_some()).count(), 32);
+        assert_eq!(cells[0].square, "a8".as_u8().unwrap());
+        assert_eq!(cells[0].piece, Some((Color::Black, PieceKind::Rook)));
+        assert_eq!(cells[63].square, "h1".as_u8().unwrap());
+        assert!(cells.iter().all(|cell| cell.highlight.is_none()));
+        for row in cells.chunks(8) {
+            assert!(row.windows(2).all(|pair| pair[0].shade != pair[1].shade));
+        }
+        assert_eq!(cells[56].shade, Shade::Dark);
+    }
+
+    #[test]
+    fn legal_squares_and_check_are_highlighted() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("f7", "f6"), ("d1", "h5")] {
+            board.do_move(position, target);
+        }
+        let legal_squares = board.get_legal_squares(&"g7".as_u8().unwrap()).unwrap();
+        let cells = board.render_cells(Some(&legal_squares));
+        let highlighted: Vec<_> = cells.iter()
+            .filter_map(|cell| Some((cell.square.as_string(), cell.highlight?)))
+            .collect();
+        assert_eq!(highlighted, vec![(String::from("e8"), Highlight::Check), (String::from("g6"), Highlight::LegalSquare)]);
+    }
+}
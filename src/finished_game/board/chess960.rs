@@ -0,0 +1,89 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::engine;
+use crate::finished_game::piece::{create_piece, Piece, PieceKind};
+use crate::square::BOARD_SIZE;
+
+/// The number of Chess960 starting positions
+pub const CHESS960_POSITIONS: u16 = 960;
+
+/// Where the two knights go among the five files still free, for each of the ten ways to place them
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+impl Board {
+    /// Creates the Chess960 starting position with number `id`, in the usual numbering where 518 is the
+    /// normal starting position, or `None` if `id` is 960 or more. Castling follows the normal rules, so
+    /// it is only possible when the king and a rook start on their usual squares.
+    pub fn new_960(id: u16) -> Option<Board> {
+        if id >= CHESS960_POSITIONS {
+            return None;
+        }
+        let mut officers = [None; BOARD_SIZE as usize];
+        let mut n = id as usize;
+        officers[2 * (n % 4) + 1] = Some(PieceKind::Bishop);
+        n /= 4;
+        officers[2 * (n % 4)] = Some(PieceKind::Bishop);
+        n /= 4;
+        officers[free_files(&officers)[n % 6]] = Some(PieceKind::Queen);
+        let (first_knight, second_knight) = KNIGHT_PLACEMENTS[n / 6];
+        let free = free_files(&officers);
+        officers[free[first_knight]] = Some(PieceKind::Knight);
+        officers[free[second_knight]] = Some(PieceKind::Knight);
+        for (file, kind) in free_files(&officers).into_iter().zip([PieceKind::Rook, PieceKind::King, PieceKind::Rook]) {
+            officers[file] = Some(kind);
+        }
+
+        let mut pieces = Vec::<Box<dyn Piece>>::new();
+        for (color, officer_rank, pawn_rank) in [(Color::White, 0, 1), (Color::Black, 7, 6)] {
+            for (file, kind) in (0..BOARD_SIZE).zip(officers) {
+                pieces.push(create_piece(PieceKind::Pawn, color, (file, pawn_rank)));
+                pieces.push(create_piece(kind.unwrap(), color, (file, officer_rank)));
+            }
+        }
+        Some(Board::from_pieces(pieces))
+    }
+
+    /// Creates a random Chess960 starting position. The same `rng` state always gives the same position.
+    pub fn new_960_random(rng: &mut u64) -> Board {
+        let id = engine::next_random(rng) % CHESS960_POSITIONS as u64;
+        Board::new_960(id as u16).unwrap()
+    }
+}
+
+/// Returns the files on the back rank without a piece yet, from the a-file
+fn free_files(officers: &[Option<PieceKind>]) -> Vec<usize> {
+    (0..officers.len()).filter(|&file| officers[file].is_none()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::piece::PieceKind;
+
+    #[test]
+    fn position_518_is_the_normal_start() {
+        assert_eq!(Board::new_960(518), Some(Board::new()));
+        assert_eq!(Board::new_960(960), None);
+    }
+
+    #[test]
+    fn random_position_follows_chess960_rules() {
+        let mut rng = 7;
+        for _ in 0..20 {
+            let board = Board::new_960_random(&mut rng);
+            let files_of = |kind: PieceKind, rank: u8| (0..8).filter(|&file| board.kind_at((file, rank)) == Some(kind)).collect::<Vec<u8>>();
+
+            let bishops = files_of(PieceKind::Bishop, 0);
+            assert_eq!(bishops.len(), 2);
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            let rooks = files_of(PieceKind::Rook, 0);
+            let king = files_of(PieceKind::King, 0)[0];
+            assert!(rooks[0] < king && king < rooks[1]);
+            for file in 0..8 {
+                assert_eq!(board.kind_at((file, 7)), board.kind_at((file, 0)));
+                assert_eq!(board.kind_at((file, 1)), Some(PieceKind::Pawn));
+                assert_eq!(board.kind_at((file, 6)), Some(PieceKind::Pawn));
+            }
+        }
+    }
+}
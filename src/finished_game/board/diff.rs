@@ -0,0 +1,55 @@
+use crate::finished_game::board::Board;
+
+/// A square that holds different pieces on two boards. The chars are the printed pieces.
+#[derive(Debug, PartialEq)]
+pub enum SquareDiff {
+    /// The square is empty on this board, but has a piece on the other
+    Added((u8, u8), char),
+    /// The square has a piece on this board, but is empty on the other
+    Removed((u8, u8), char),
+    /// The square has different pieces on the two boards
+    Changed((u8, u8), char, char),
+}
+
+impl Board {
+    /// Returns the squares that differ between this board and `other`, sorted by square
+    pub fn diff(&self, other: &Board) -> Vec<SquareDiff> {
+        let mut squares: Vec<(u8, u8)> = self.pieces.keys().chain(other.pieces.keys()).cloned().collect();
+        squares.sort();
+        squares.dedup();
+        squares.into_iter()
+            .filter_map(|square| match (self.pieces.get(&square), other.pieces.get(&square)) {
+                (Some(piece), None) => Some(SquareDiff::Removed(square, piece.print())),
+                (None, Some(piece)) => Some(SquareDiff::Added(square, piece.print())),
+                (Some(piece), Some(other_piece)) if piece.print() != other_piece.print() => {
+                    Some(SquareDiff::Changed(square, piece.print(), other_piece.print()))
+                }
+                _ => None
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::board::diff::SquareDiff;
+    use crate::square::Square;
+
+    #[test]
+    fn diff_after_pawn_move_has_two_squares() {
+        let board = Board::new();
+        let mut other = Board::new();
+        other.do_move("e2", "e4");
+        assert_eq!(board.diff(&other), vec![
+            SquareDiff::Removed("e2".as_u8().unwrap(), '♟'),
+            SquareDiff::Added("e4".as_u8().unwrap(), '♟'),
+        ])
+    }
+
+    #[test]
+    fn diff_with_itself_is_empty() {
+        let board = Board::new();
+        assert_eq!(board.diff(&board.clone()), vec![])
+    }
+}
@@ -0,0 +1,80 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+
+impl Board {
+    /// Returns the square a pawn skipped over with a double step on the last move, where it can be
+    /// captured en passant
+    pub fn en_passant_target(&self) -> Option<(u8, u8)> {
+        self.en_passant
+    }
+
+    /// Returns the square of the pawn that is captured en passant if the piece at `position` moves to
+    /// `target_square`, or `None` if the move is not an en passant capture
+    pub(crate) fn en_passant_captured_square(&self, position: &(u8, u8), target_square: (u8, u8)) -> Option<(u8, u8)> {
+        let piece = self.pieces.get(position)?;
+        let captured_square = (target_square.0, position.1);
+        let is_rival_pawn = self.pieces.get(&captured_square)
+            .is_some_and(|rival| rival.get_kind() == PieceKind::Pawn && rival.get_color() != piece.get_color());
+        let is_forward_diagonal = position.0.abs_diff(target_square.0) == 1 && match piece.get_color() {
+            Color::White => target_square.1 == position.1 + 1,
+            Color::Black => target_square.1 + 1 == position.1,
+        };
+        match piece.get_kind() == PieceKind::Pawn && self.en_passant == Some(target_square) && is_forward_diagonal && is_rival_pawn {
+            true => Some(captured_square),
+            false => None,
+        }
+    }
+
+    /// Returns the en passant capture of the pawn at `position` if it is legal
+    pub(crate) fn legal_en_passant(&self, position: &(u8, u8)) -> Option<(u8, u8)> {
+        let target_square = self.en_passant?;
+        self.en_passant_captured_square(position, target_square)?;
+        let color = self.color_at(*position)?;
+        let mut new_board = self.clone();
+        new_board.apply_move(position, target_square).ok()?;
+        match new_board.is_check(color) {
+            true => None,
+            false => Some(target_square),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn double_push_sets_en_passant_target() {
+        let mut board = Board::new();
+        assert_eq!(board.en_passant_target(), None);
+        board.do_move("e2", "e4");
+        assert_eq!(board.en_passant_target(), Some("e3".as_u8().unwrap()));
+        board.do_move("g8", "f6");
+        assert_eq!(board.en_passant_target(), None);
+        board.do_move("e4", "e5");
+        assert_eq!(board.en_passant_target(), None);
+    }
+
+    #[test]
+    fn capture_en_passant_and_take_back() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("a7", "a6");
+        board.do_move("e4", "e5");
+        board.do_move("d7", "d5");
+        assert!(board.get_legal_squares(&"e5".as_u8().unwrap()).unwrap().contains(&"d6".as_u8().unwrap()));
+        assert_eq!(board.to_san(board.move_from_uci("e5d6").unwrap()).unwrap(), "exd6");
+
+        let captured = board.apply_move(&"e5".as_u8().unwrap(), "d6".as_u8().unwrap()).unwrap().unwrap();
+        assert_eq!(captured.get_kind(), PieceKind::Pawn);
+        assert_eq!(board.kind_at("d5".as_u8().unwrap()), None);
+
+        board.undo_move();
+        assert_eq!(board.kind_at("d5".as_u8().unwrap()), Some(PieceKind::Pawn));
+        assert_eq!(board.kind_at("d6".as_u8().unwrap()), None);
+        assert_eq!(board.en_passant_target(), Some("d6".as_u8().unwrap()));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::board::ascii::AsciiError;
+use crate::finished_game::board::castling::CastlingRights;
+use crate::finished_game::color::Color;
+use crate::finished_game::engine;
+use crate::finished_game::piece::PieceKind;
+use crate::square::Square;
+
+/// Why `from_fen` could not read a position
+#[derive(Debug, PartialEq)]
+pub enum FenError {
+    /// The FEN does not have four to six fields separated by spaces
+    WrongFieldCount(usize),
+    /// The piece placement is not 8 ranks of 8 squares with known pieces
+    InvalidPlacement(AsciiError),
+    /// The color to move or one of the move counters can not be read
+    InvalidField(String),
+    /// The castling field has other letters than `KQkq`, or the same letter twice
+    InvalidCastling(String),
+    /// The castling field gives a right to castle where the king or the rook is not on its starting square
+    InconsistentCastling(String),
+    /// The en passant field is not a square on rank 6 with white to move or rank 3 with black to move,
+    /// right behind a pawn of the opponent that could just have made a double step
+    InvalidEnPassant(String),
+}
+
+impl Board {
+    /// Returns the position in Forsyth–Edwards Notation,
+    /// for example `"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"`
+    pub fn to_fen(&self) -> String {
+        let placement: Vec<String> = self.to_ascii().lines()
+            .map(|row| {
+                let mut fen_row = String::new();
+                let mut empty = 0;
+                for square in row.chars() {
+                    if square == '.' {
+                        empty += 1;
+                        continue;
+                    }
+                    if empty > 0 {
+                        fen_row.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    fen_row.push(square);
+                }
+                if empty > 0 {
+                    fen_row.push_str(&empty.to_string());
+                }
+                fen_row
+            })
+            .collect();
+        let active_color = match self.active_color {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+        let rights = self.castling_rights;
+        let mut castling: String = [(rights.white_king_side, 'K'), (rights.white_queen_side, 'Q'), (rights.black_king_side, 'k'), (rights.black_queen_side, 'q')]
+            .iter()
+            .filter(|(allowed, _)| *allowed)
+            .map(|(_, letter)| letter)
+            .collect();
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        let en_passant = self.en_passant.map_or(String::from("-"), |square| square.as_string());
+        format!("{} {} {} {} {} {}", placement.join("/"), active_color, castling, en_passant, self.halfmove_clock, self.fullmove_number)
+    }
+
+    /// Returns the first four fields of `to_fen`: the placement, the color to move, the castling rights and
+    /// the en passant square. Positions with the same key are the same position, whatever the move counters say.
+    pub fn position_key(&self) -> String {
+        self.to_fen().split(' ').take(4).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Creates a board from a position in Forsyth–Edwards Notation, as written by `to_fen`.
+    /// The two move counters may be left out, and then count from the start of a game.
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if !(4..=6).contains(&fields.len()) {
+            return Err(FenError::WrongFieldCount(fields.len()));
+        }
+        let ascii: Vec<String> = fields[0].split('/')
+            .map(|rank| rank.chars()
+                .map(|square| match square.to_digit(10) {
+                    Some(empty) => ".".repeat(empty as usize),
+                    None => square.to_string(),
+                })
+                .collect())
+            .collect();
+        let mut board = Board::from_ascii(&ascii.join("\n")).map_err(FenError::InvalidPlacement)?;
+        let invalid = |field: &str| FenError::InvalidField(field.to_string());
+
+        board.active_color = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            field => return Err(invalid(field)),
+        };
+        board.castling_rights = CastlingRights::none();
+        let rights = &mut board.castling_rights;
+        for letter in fields[2].chars().filter(|_| fields[2] != "-") {
+            let right = match letter {
+                'K' => &mut rights.white_king_side,
+                'Q' => &mut rights.white_queen_side,
+                'k' => &mut rights.black_king_side,
+                'q' => &mut rights.black_queen_side,
+                _ => return Err(FenError::InvalidCastling(fields[2].to_string())),
+            };
+            if *right {
+                return Err(FenError::InvalidCastling(fields[2].to_string()));
+            }
+            *right = true;
+        }
+        let (rights, possible) = (board.castling_rights, board.castling_rights_from_placement());
+        let castling_pieces_home = [
+            (rights.white_king_side, possible.white_king_side),
+            (rights.white_queen_side, possible.white_queen_side),
+            (rights.black_king_side, possible.black_king_side),
+            (rights.black_queen_side, possible.black_queen_side),
+        ].iter().all(|&(right, possible)| !right || possible);
+        if !castling_pieces_home {
+            return Err(FenError::InconsistentCastling(fields[2].to_string()));
+        }
+        board.en_passant = match fields[3] {
+            "-" => None,
+            field => {
+                let invalid_en_passant = || FenError::InvalidEnPassant(field.to_string());
+                let square = field.as_u8().ok_or_else(invalid_en_passant)?;
+                // The rank of the en passant square, of the pawn that double stepped past it and of where the pawn came from
+                let (rank, pawn_rank, start_rank) = match board.active_color {
+                    Color::White => (5, 4, 6),
+                    Color::Black => (2, 3, 1),
+                };
+                let pawn_just_double_stepped = square.1 == rank
+                    && board.is_piece((square.0, pawn_rank), board.active_color.opposite(), PieceKind::Pawn)
+                    && !board.pieces.contains_key(&square)
+                    && !board.pieces.contains_key(&(square.0, start_rank));
+                if !pawn_just_double_stepped {
+                    return Err(invalid_en_passant());
+                }
+                Some(square)
+            }
+        };
+        board.halfmove_clock = fields.get(4).map_or(Ok(0), |field| field.parse().map_err(|_| invalid(field)))?;
+        board.fullmove_number = fields.get(5).map_or(Ok(1), |field| field.parse().map_err(|_| invalid(field)))?;
+        board.position_history = vec![board.position_key()];
+        Ok(board)
+    }
+
+    /// Returns the number of the current move. It starts at 1 and goes up after every move by black.
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    /// Returns a one line description of the position for logs, like
+    /// `"<fen> | to move: White | eval: +0.3 | legal: 20"`. The evaluation is counted in pawns.
+    pub fn summary(&self) -> String {
+        let legal_moves = self.mobility(self.active_color);
+        format!(
+            "{} | to move: {:?} | eval: {:+.1} | legal: {}",
+            self.to_fen(),
+            self.active_color,
+            engine::evaluate(self) as f64 / 100.0,
+            legal_moves
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::board::fen::FenError;
+
+    #[test]
+    fn start_position_fen() {
+        assert_eq!(Board::new().to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    }
+
+    #[test]
+    fn fen_after_first_move() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    }
+
+    #[test]
+    fn fullmove_number_goes_up_after_black_moves() {
+        let mut board = Board::new();
+        assert!(board.to_fen().ends_with(" 0 1"));
+        board.do_move("e2", "e4");
+        board.do_move("e7", "e5");
+        assert!(board.to_fen().ends_with(" 0 2"));
+        board.do_move("g1", "f3");
+        assert!(board.to_fen().ends_with(" 1 2"));
+    }
+
+    #[test]
+    fn start_position_summary() {
+        let summary = Board::new().summary();
+        assert!(summary.contains(" w "));
+        assert!(summary.contains("20"));
+        assert_eq!(summary, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 | to move: White | eval: +0.0 | legal: 20");
+    }
+
+    #[test]
+    fn from_fen_reads_what_to_fen_writes() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("c7", "c5"), ("e4", "e5"), ("d7", "d5")] {
+            board.do_move(position, target);
+        }
+        let fen = board.to_fen();
+        assert_eq!(fen, "rnbqkbnr/pp2pppp/8/2ppP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3");
+        let read = Board::from_fen(&fen).unwrap();
+        assert_eq!(read, board);
+        assert_eq!(read.to_fen(), fen);
+        assert_eq!(read.zobrist_hash(), board.zobrist_hash());
+    }
+
+    #[test]
+    fn from_fen_rejects_bad_fields() {
+        assert_eq!(Board::from_fen("8/8/8/8/8/8/8/8 w").err(), Some(FenError::WrongFieldCount(2)));
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 x - -").err(), Some(FenError::InvalidField(String::from("x"))));
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 w KX -").err(), Some(FenError::InvalidCastling(String::from("KX"))));
+        assert!(matches!(Board::from_fen("4k3/8/8/8/8/8/8/4K2 w - -"), Err(FenError::InvalidPlacement(_))));
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 3 40").unwrap().to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 3 40");
+    }
+
+    #[test]
+    fn from_fen_rejects_bad_castling_field() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR";
+        assert_eq!(Board::from_fen(&format!("{} w KQxq -", start)).err(), Some(FenError::InvalidCastling(String::from("KQxq"))));
+        assert_eq!(Board::from_fen(&format!("{} w KKq -", start)).err(), Some(FenError::InvalidCastling(String::from("KKq"))));
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 w K -").err(), Some(FenError::InconsistentCastling(String::from("K"))));
+    }
+
+    #[test]
+    fn from_fen_rejects_en_passant_square_on_wrong_rank() {
+        let after_d5 = "rnbqkbnr/pp2pppp/8/2ppP3/8/8/PPPP1PPP/RNBQKBNR";
+        assert!(Board::from_fen(&format!("{} w KQkq d6 0 3", after_d5)).is_ok());
+        assert_eq!(Board::from_fen(&format!("{} w KQkq d3 0 3", after_d5)).err(), Some(FenError::InvalidEnPassant(String::from("d3"))));
+        assert_eq!(Board::from_fen(&format!("{} b KQkq d6 0 3", after_d5)).err(), Some(FenError::InvalidEnPassant(String::from("d6"))));
+        assert_eq!(Board::from_fen(&format!("{} w KQkq e6 0 3", after_d5)).err(), Some(FenError::InvalidEnPassant(String::from("e6"))));
+        assert_eq!(Board::from_fen(&format!("{} w KQkq d9 0 3", after_d5)).err(), Some(FenError::InvalidEnPassant(String::from("d9"))));
+    }
+
+    #[test]
+    fn position_key_ignores_move_counters() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 30").unwrap();
+        let later = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 12 36").unwrap();
+        assert_eq!(board.position_key(), "4k3/8/8/8/8/8/8/4K2R w K -");
+        assert_eq!(board.position_key(), later.position_key());
+        assert_ne!(board.to_fen(), later.to_fen());
+    }
+}
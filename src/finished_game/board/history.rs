@@ -0,0 +1,318 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::finished_game::board::{Board, Move, MoveKind};
+use crate::finished_game::board::castling::{castling_rook_move, CastlingRights};
+use crate::finished_game::board::legality::MoveError;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::Piece;
+
+/// A move as it was played, with the board state it changed
+#[derive(Clone)]
+pub(crate) struct UndoData {
+    pub(crate) mv: Move,
+    /// The moving piece as it was before the move, so a promotion can be taken back
+    pub(crate) moved_piece: Box<dyn Piece>,
+    pub(crate) captured_piece: Option<Box<dyn Piece>>,
+    pub(crate) halfmove_clock: u32,
+    pub(crate) castling_rights: CastlingRights,
+    pub(crate) en_passant: Option<(u8, u8)>,
+}
+
+/// What `make_null_move` changed, so `unmake_null_move` can put it back
+pub struct NullMoveUndo {
+    /// The color that passed the turn
+    color: Color,
+    halfmove_clock: u32,
+    en_passant: Option<(u8, u8)>,
+}
+
+/// What kind of move the last move was, for example to play a different sound for each
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MoveInfo {
+    pub capture: bool,
+    pub castling: bool,
+    pub promotion: bool,
+    pub en_passant: bool,
+    /// The move put the opponent in check
+    pub check: bool,
+}
+
+/// A move made with `make_guarded`. Gives access to the board, and takes the move back, together with
+/// any moves made after it, when it goes out of scope.
+pub struct MoveGuard<'a> {
+    board: &'a mut Board,
+    /// The number of half moves played before the guarded move
+    ply: usize,
+}
+
+impl Deref for MoveGuard<'_> {
+    type Target = Board;
+
+    fn deref(&self) -> &Board {
+        self.board
+    }
+}
+
+impl DerefMut for MoveGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Board {
+        self.board
+    }
+}
+
+impl Drop for MoveGuard<'_> {
+    fn drop(&mut self) {
+        self.board.truncate_history(self.ply);
+    }
+}
+
+impl Board {
+    /// Makes `mv` like `make_move`, and returns a guard that takes it back when dropped
+    pub fn make_guarded(&mut self, mv: Move) -> Result<MoveGuard<'_>, MoveError> {
+        let ply = self.move_history.len();
+        self.make_move(mv)?;
+        Ok(MoveGuard { board: self, ply })
+    }
+
+    /// Passes the turn to the opponent without moving, for null move pruning in the search. The returned
+    /// undo data goes back to `unmake_null_move`. Returns `None` without changing the board when the color
+    /// to move is in check.
+    pub fn make_null_move(&mut self) -> Option<NullMoveUndo> {
+        if self.is_check(self.active_color) {
+            return None;
+        }
+        let undo = NullMoveUndo { color: self.active_color, halfmove_clock: self.halfmove_clock, en_passant: self.en_passant };
+        self.legal_moves_cache.get_mut().take();
+        if self.active_color == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.active_color = self.active_color.opposite();
+        self.en_passant = None;
+        self.halfmove_clock += 1;
+        self.position_history.push(self.position_key());
+        Some(undo)
+    }
+
+    /// Takes back a null move made with `make_null_move`
+    pub fn unmake_null_move(&mut self, undo: NullMoveUndo) {
+        self.legal_moves_cache.get_mut().take();
+        self.active_color = undo.color;
+        if self.active_color == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        self.halfmove_clock = undo.halfmove_clock;
+        self.en_passant = undo.en_passant;
+        self.position_history.pop();
+    }
+
+    /// Returns every move played on this board, in order
+    pub fn move_history(&self) -> Vec<Move> {
+        self.move_history.iter().map(|undo| undo.mv).collect()
+    }
+
+    /// Returns what kind of move the last move was, or `None` before the first move
+    pub fn last_move_info(&self) -> Option<MoveInfo> {
+        let undo = self.move_history.last()?;
+        Some(MoveInfo {
+            capture: undo.captured_piece.is_some(),
+            castling: undo.mv.kind == MoveKind::Castle,
+            promotion: undo.mv.promotion.is_some(),
+            en_passant: undo.mv.kind == MoveKind::EnPassant,
+            check: self.is_check(self.active_color),
+        })
+    }
+
+    /// Returns the pieces of `color` that have been captured, in the order they were taken
+    pub fn captured_pieces(&self, color: Color) -> Vec<&dyn Piece> {
+        self.move_history.iter()
+            .filter_map(|undo| undo.captured_piece.as_deref())
+            .filter(|piece| piece.get_color() == color)
+            .collect()
+    }
+
+    /// Takes back the last move and returns it, or `None` if no moves have been played
+    pub fn undo_move(&mut self) -> Option<Move> {
+        let undo = self.move_history.pop()?;
+        self.legal_moves_cache.get_mut().take();
+        self.pieces.remove(&undo.mv.to);
+        if let Some(captured_piece) = undo.captured_piece {
+            self.pieces.insert(*captured_piece.get_position(), captured_piece);
+        }
+        self.active_color = undo.moved_piece.get_color();
+        if self.active_color == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        let rook_move = castling_rook_move(undo.mv.from, undo.mv.to).filter(|_| undo.mv.kind == MoveKind::Castle);
+        if let Some((rook_position, rook_target)) = rook_move {
+            if let Some(mut rook) = self.pieces.remove(&rook_target) {
+                rook.move_piece(rook_position);
+                self.pieces.insert(rook_position, rook);
+            }
+        }
+        self.pieces.insert(undo.mv.from, undo.moved_piece);
+        self.halfmove_clock = undo.halfmove_clock;
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.position_history.pop();
+        Some(undo.mv)
+    }
+
+    /// Takes back every move after the first `ply` half moves, so another line can be tried from there
+    pub fn truncate_history(&mut self, ply: usize) {
+        while self.move_history.len() > ply {
+            self.undo_move();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::{Board, Move, MoveKind};
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn undo_restores_captured_piece() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        let before_capture = board.clone();
+        board.do_move("e4", "d5");
+        board.undo_move();
+        assert_eq!(board.diff(&before_capture), vec![]);
+        assert_eq!(board.to_fen(), before_capture.to_fen());
+        assert_eq!(board.zobrist_hash(), before_capture.zobrist_hash());
+    }
+
+    #[test]
+    fn undo_takes_back_king_jump_without_rook() {
+        let mut board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            ....KN..
+        ").unwrap();
+        let before = board.clone();
+        board.apply_move(&"e1".as_u8().unwrap(), "g1".as_u8().unwrap()).unwrap();
+        assert_eq!(board.last_move().unwrap().kind, MoveKind::Quiet);
+        board.undo_move();
+        assert_eq!(board.diff(&before), vec![]);
+        assert_eq!(board.to_fen(), before.to_fen());
+    }
+
+    #[test]
+    fn captured_pieces_by_color() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("d7", "d5"), ("e4", "d5"), ("d8", "d5")] {
+            board.do_move(position, target);
+        }
+        let captured_black: Vec<PieceKind> = board.captured_pieces(Color::Black).iter().map(|piece| piece.get_kind()).collect();
+        assert_eq!(captured_black, vec![PieceKind::Pawn]);
+        assert_eq!(board.captured_pieces(Color::White).len(), 1);
+    }
+
+    #[test]
+    fn capturing_check_is_both_capture_and_check() {
+        let mut board = Board::new();
+        assert_eq!(board.last_move_info(), None);
+        for (position, target) in [("e2", "e4"), ("e7", "e5"), ("d1", "h5"), ("b8", "c6"), ("f1", "c4"), ("g8", "f6"), ("h5", "f7")] {
+            board.do_move(position, target);
+        }
+        let info = board.last_move_info().unwrap();
+        assert!(info.capture && info.check);
+        assert!(!info.castling && !info.promotion && !info.en_passant);
+    }
+
+    #[test]
+    fn undo_takes_back_promotion() {
+        let mut board = Board::from_ascii("
+            .......k
+            P.......
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        board.do_move("a7", "a8");
+        assert_eq!(board.move_history()[0].promotion, Some(PieceKind::Queen));
+        board.undo_move();
+        assert_eq!(board.kind_at("a7".as_u8().unwrap()), Some(PieceKind::Pawn));
+        assert_eq!(board.kind_at("a8".as_u8().unwrap()), None);
+    }
+
+    #[test]
+    fn truncate_history_and_play_other_move() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")] {
+            board.do_move(position, target);
+        }
+        board.truncate_history(2);
+        assert_eq!(board.move_history().len(), 2);
+        board.do_move("f1", "c4");
+
+        let history = board.move_history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2], Move { from: "f1".as_u8().unwrap(), to: "c4".as_u8().unwrap(), promotion: None, kind: MoveKind::Quiet });
+        assert_eq!(board.kind_at("g1".as_u8().unwrap()), Some(PieceKind::Knight));
+    }
+
+    #[test]
+    fn two_null_moves_return_to_the_position() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        let before = board.clone();
+
+        let first = board.make_null_move().unwrap();
+        assert_eq!(board.get_active_color(), Color::White);
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2");
+        let second = board.make_null_move().unwrap();
+        board.unmake_null_move(second);
+        board.unmake_null_move(first);
+        assert_eq!(board.to_fen(), before.to_fen());
+        assert_eq!(board.zobrist_hash(), before.zobrist_hash());
+    }
+
+    #[test]
+    fn no_null_move_in_check() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("f7", "f6"), ("d1", "h5")] {
+            board.do_move(position, target);
+        }
+        let fen = board.to_fen();
+        assert!(board.make_null_move().is_none());
+        assert_eq!(board.to_fen(), fen);
+    }
+
+    /// Plays `moves` in order, each with a guard, and returns early when a move gives check
+    fn plays_a_check(board: &mut Board, moves: &[Move]) -> bool {
+        let Some((&mv, rest)) = moves.split_first() else { return false };
+        let mut guard = board.make_guarded(mv).unwrap();
+        if guard.is_check(guard.get_active_color()) {
+            return true;
+        }
+        plays_a_check(&mut guard, rest)
+    }
+
+    #[test]
+    fn guarded_moves_are_taken_back_when_the_guard_is_dropped() {
+        let mut board = Board::new();
+        let moves: Vec<Move> = [("e2", "e4"), ("f7", "f6"), ("d1", "h5"), ("g7", "g6")]
+            .iter()
+            .map(|(from, to)| board.create_move(from.as_u8().unwrap(), to.as_u8().unwrap(), None))
+            .collect();
+        let before = board.clone();
+
+        assert!(!plays_a_check(&mut board, &moves[..2]));
+        assert_eq!(board.to_fen(), before.to_fen());
+        assert!(plays_a_check(&mut board, &moves));
+        assert_eq!(board.to_fen(), before.to_fen());
+        assert_eq!(board.zobrist_hash(), before.zobrist_hash());
+        assert_eq!(board.move_history(), vec![]);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::finished_game::board::{Board, Move};
+use crate::finished_game::error::ChessError;
+use crate::finished_game::piece::{Piece, PieceKind};
+
+/// Why a move is not allowed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IllegalReason {
+    NoPiece,
+    WrongColor,
+    OccupiedByOwnPiece,
+    Unreachable,
+    LeavesKingInCheck,
+}
+
+impl IllegalReason {
+    /// The message shown to the player when the move is rejected
+    pub fn message(&self) -> &'static str {
+        match self {
+            IllegalReason::NoPiece => "Det er inga brikke i feltet du valde",
+            IllegalReason::WrongColor => "Du valde ei brikke som tilhøyrer motstandaren",
+            IllegalReason::OccupiedByOwnPiece => "Feltet er opptatt av ei av dine eigne brikker",
+            IllegalReason::Unreachable => "Brikka kan ikkje flytte til feltet du valde",
+            IllegalReason::LeavesKingInCheck => "Trekket set din eigen konge i sjakk",
+        }
+    }
+}
+
+/// Whether a piece may move to a square it reaches, or what stops it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveVerdict {
+    Legal,
+    /// One of the player's own pieces stands on the square
+    BlockedByFriendly,
+    /// The piece reaches the square, but moving there leaves the player's own king in check
+    LeavesKingInCheck,
+}
+
+/// Why `make_move` rejected a move
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveError {
+    Illegal(IllegalReason),
+    /// The promotion is not to a queen, rook, bishop or knight, or the move is not a promotion
+    InvalidPromotion,
+    /// The kind of the move does not match what the move does in this position
+    WrongKind,
+}
+
+impl Board {
+    /// Returns why moving the piece at `position` to `target_square` is illegal for the color to move,
+    /// or `None` if the move is legal
+    pub fn explain_illegal(&self, position: &(u8, u8), target_square: (u8, u8)) -> Option<IllegalReason> {
+        let Some(piece) = self.pieces.get(position) else { return Some(IllegalReason::NoPiece) };
+        let color = piece.get_color();
+        let team = self.get_positions(color);
+        let rival_team = self.get_positions(color.opposite());
+
+        if color != self.active_color {
+            Some(IllegalReason::WrongColor)
+        } else if team.contains(&target_square) {
+            Some(IllegalReason::OccupiedByOwnPiece)
+        } else if self.all_legal_moves(color).get(position).is_some_and(|legal_squares| legal_squares.contains(&target_square)) {
+            None
+        } else if piece.get_moves(&team, &rival_team).contains(&target_square) && self.move_leaves_self_in_check(position, target_square) {
+            Some(IllegalReason::LeavesKingInCheck)
+        } else {
+            Some(IllegalReason::Unreachable)
+        }
+    }
+
+    /// Returns true if the piece at `from` may move to `to` whichever color is to move, for analysis boards
+    /// where both sides move freely. The move must still follow the rules for the piece and not leave
+    /// its own king in check.
+    pub fn is_legal_ignoring_turn(&self, from: (u8, u8), to: (u8, u8)) -> bool {
+        self.get_legal_squares(&from).is_ok_and(|legal_squares| legal_squares.contains(&to))
+    }
+
+    /// Returns every square the piece at `position` reaches, up to and including the first piece in each
+    /// direction, with whether it may move there. Shows learners which moves the pin and check rules remove.
+    pub fn legal_squares_explained(&self, position: &(u8, u8)) -> Result<HashMap<(u8, u8), MoveVerdict>, ChessError> {
+        let legal_squares = self.get_legal_squares(position)?;
+        let piece = &self.pieces[position];
+        let team = self.get_positions(piece.get_color());
+        let occupied: HashSet<(u8, u8)> = self.pieces.keys().copied().collect();
+        let mut verdicts: HashMap<(u8, u8), MoveVerdict> = piece.get_moves(&HashSet::new(), &occupied).into_iter()
+            .map(|square| {
+                let verdict = if team.contains(&square) {
+                    MoveVerdict::BlockedByFriendly
+                } else if legal_squares.contains(&square) {
+                    MoveVerdict::Legal
+                } else {
+                    MoveVerdict::LeavesKingInCheck
+                };
+                (square, verdict)
+            })
+            .collect();
+        verdicts.extend(legal_squares.into_iter().map(|square| (square, MoveVerdict::Legal)));
+        Ok(verdicts)
+    }
+
+    /// Makes the move if it is legal for the color to move, returning the captured piece if any
+    pub fn try_move(&mut self, position: &(u8, u8), target_square: (u8, u8)) -> Result<Option<Box<dyn Piece>>, IllegalReason> {
+        match self.explain_illegal(position, target_square) {
+            Some(reason) => Err(reason),
+            None => self.apply_move(position, target_square).map_err(|_| IllegalReason::NoPiece),
+        }
+    }
+
+    /// Makes `mv` if it is legal for the color to move. This is the one place a move updates the pieces,
+    /// castling rights, en passant square, clocks, history and the color to move together.
+    /// A pawn reaching the last rank without a promotion becomes a queen.
+    pub fn make_move(&mut self, mv: Move) -> Result<(), MoveError> {
+        if let Some(reason) = self.explain_illegal(&mv.from, mv.to) {
+            return Err(MoveError::Illegal(reason));
+        }
+        if mv.kind != self.move_kind(&mv.from, mv.to) {
+            return Err(MoveError::WrongKind);
+        }
+        let promotion = match (self.is_promotion(&mv.from, mv.to), mv.promotion) {
+            (true, None) => PieceKind::Queen,
+            (true, Some(kind)) if ![PieceKind::Pawn, PieceKind::King].contains(&kind) => kind,
+            (false, None) => PieceKind::Queen,
+            _ => return Err(MoveError::InvalidPromotion),
+        };
+        self.apply_move_with_promotion(&mv.from, mv.to, promotion).map_err(|_| MoveError::Illegal(IllegalReason::NoPiece))?;
+        Ok(())
+    }
+
+    /// Returns the last move played, or `None` before the first move
+    pub fn last_move(&self) -> Option<Move> {
+        self.move_history.last().map(|undo| undo.mv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::{Board, Move, MoveKind};
+    use crate::finished_game::board::legality::{IllegalReason, MoveError, MoveVerdict};
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn wrong_color_message_is_norwegian() {
+        assert_eq!(IllegalReason::WrongColor.message(), "Du valde ei brikke som tilhøyrer motstandaren")
+    }
+
+    #[test]
+    fn explain_illegal_moves() {
+        let mut board = Board::new();
+        let explain = |board: &Board, position: &str, target: &str| {
+            board.explain_illegal(&position.as_u8().unwrap(), target.as_u8().unwrap())
+        };
+        assert_eq!(explain(&board, "e3", "e4"), Some(IllegalReason::NoPiece));
+        assert_eq!(explain(&board, "e7", "e5"), Some(IllegalReason::WrongColor));
+        assert_eq!(explain(&board, "a1", "a2"), Some(IllegalReason::OccupiedByOwnPiece));
+        assert_eq!(explain(&board, "e2", "e5"), Some(IllegalReason::Unreachable));
+        assert_eq!(explain(&board, "e2", "e4"), None);
+
+        board.do_move("f2", "f3");
+        board.do_move("e7", "e5");
+        board.do_move("f3", "f4");
+        board.do_move("d8", "h4");
+        assert_eq!(explain(&board, "a2", "a3"), Some(IllegalReason::LeavesKingInCheck));
+    }
+
+    #[test]
+    fn try_move_rejects_illegal_move() {
+        let mut board = Board::new();
+        assert_eq!(board.try_move(&"e7".as_u8().unwrap(), "e5".as_u8().unwrap()).err(), Some(IllegalReason::WrongColor));
+        assert!(board.try_move(&"e2".as_u8().unwrap(), "e4".as_u8().unwrap()).is_ok());
+        assert!(board.try_move(&"e7".as_u8().unwrap(), "e5".as_u8().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn game_played_with_make_move() {
+        let mut board = Board::new();
+        let mut moves = Vec::new();
+        for (from, to) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6"), ("f1", "c4"), ("f8", "c5"), ("e1", "g1"), ("g8", "f6")] {
+            let mv = board.create_move(from.as_u8().unwrap(), to.as_u8().unwrap(), None);
+            board.make_move(mv).unwrap();
+            moves.push(mv);
+        }
+        assert_eq!(board.to_fen(), "r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 w kq - 6 5");
+        assert_eq!(board.move_history(), moves);
+        assert_eq!(board.last_move(), moves.last().copied());
+    }
+
+    #[test]
+    fn make_move_rejects_wrong_color_and_bad_promotion() {
+        let mut board = Board::new();
+        let mv = |from: &str, to: &str, promotion| Move { from: from.as_u8().unwrap(), to: to.as_u8().unwrap(), promotion, kind: MoveKind::DoublePush };
+        assert_eq!(board.make_move(mv("e7", "e5", None)), Err(MoveError::Illegal(IllegalReason::WrongColor)));
+        assert_eq!(board.make_move(mv("e2", "e4", Some(PieceKind::Queen))), Err(MoveError::InvalidPromotion));
+        assert_eq!(board.last_move(), None);
+    }
+
+    #[test]
+    fn black_moves_first_when_turn_is_ignored() {
+        let board = Board::from_fen("4k3/4n3/8/8/8/8/8/K3R3 w - - 0 1").unwrap();
+        let square = |name: &str| name.as_u8().unwrap();
+        assert_eq!(board.explain_illegal(&square("e8"), square("d8")), Some(IllegalReason::WrongColor));
+        assert!(board.is_legal_ignoring_turn(square("e8"), square("d8")));
+        assert!(!board.is_legal_ignoring_turn(square("e7"), square("c6")));
+        assert!(!board.is_legal_ignoring_turn(square("e8"), square("e6")));
+        assert!(!board.is_legal_ignoring_turn(square("d4"), square("d5")));
+    }
+
+    #[test]
+    fn pinned_pawn_squares_are_explained() {
+        let mut board = Board::new();
+        board.do_move("f7", "f5");
+        board.do_move("d1", "h5");
+        board.do_move("g7", "g6");
+        let verdicts = board.legal_squares_explained(&"g6".as_u8().unwrap()).unwrap();
+        assert_eq!(verdicts.len(), 3);
+        assert_eq!(verdicts[&"g5".as_u8().unwrap()], MoveVerdict::LeavesKingInCheck);
+        assert_eq!(verdicts[&"f5".as_u8().unwrap()], MoveVerdict::BlockedByFriendly);
+        assert_eq!(verdicts[&"h5".as_u8().unwrap()], MoveVerdict::Legal);
+    }
+
+    #[test]
+    fn castling_move_moves_the_rook() {
+        let mut board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            ....K..R
+        ").unwrap();
+        let castle = Move { from: "e1".as_u8().unwrap(), to: "g1".as_u8().unwrap(), promotion: None, kind: MoveKind::Castle };
+        assert_eq!(board.create_move(castle.from, castle.to, None), castle);
+        assert_eq!(board.make_move(Move { kind: MoveKind::Quiet, ..castle }), Err(MoveError::WrongKind));
+
+        board.make_move(castle).unwrap();
+        assert_eq!(board.kind_at("g1".as_u8().unwrap()), Some(PieceKind::King));
+        assert_eq!(board.kind_at("f1".as_u8().unwrap()), Some(PieceKind::Rook));
+        assert_eq!(board.kind_at("h1".as_u8().unwrap()), None);
+        assert!(board.last_move_info().unwrap().castling);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+use crate::square::{BOARD_SIZE, Square};
+
+impl Board {
+    /// Returns the pawns of `color` sharing their file with another pawn of the same color
+    pub fn doubled_pawns(&self, color: Color) -> Vec<(u8, u8)> {
+        let pawns = self.get_pawn_positions(color);
+        pawns.iter()
+            .filter(|pawn| pawns.iter().any(|other| other != *pawn && other.0 == pawn.0))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the pawns of `color` with no pawn of the same color on neighbouring files
+    pub fn isolated_pawns(&self, color: Color) -> Vec<(u8, u8)> {
+        let pawns = self.get_pawn_positions(color);
+        pawns.iter()
+            .filter(|pawn| !pawns.iter().any(|other| other.0.abs_diff(pawn.0) == 1))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the pawns of `color` with no rival pawns in front of them on the same or neighbouring files
+    pub fn passed_pawns(&self, color: Color) -> Vec<(u8, u8)> {
+        let rival_pawns = self.get_pawn_positions(color.opposite());
+        self.get_pawn_positions(color).into_iter()
+            .filter(|pawn| !rival_pawns.iter().any(|rival| {
+                let is_in_front = match color {
+                    Color::White => rival.1 > pawn.1,
+                    Color::Black => rival.1 < pawn.1,
+                };
+                rival.0.abs_diff(pawn.0) <= 1 && is_in_front
+            }))
+            .collect()
+    }
+
+    /// Returns how open the king of `color` is to attack: one for each file on or next to the king
+    /// without a pawn of `color`, and one for each diagonal of two or more squares from the king
+    /// toward the opponent without a pawn of `color` on it
+    pub fn king_exposure(&self, color: Color) -> u32 {
+        let Ok(&king) = self.get_king_position(color) else { return 0 };
+        let pawns = self.get_pawn_positions(color);
+        let open_files = (king.0.saturating_sub(1)..=(king.0 + 1).min(BOARD_SIZE - 1))
+            .filter(|&file| !pawns.iter().any(|pawn| pawn.0 == file))
+            .count();
+        let forward = match color {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+        let diagonal_end = |dx: i8| {
+            let mut square = king;
+            while let Some(next) = (square.0 as i8 + dx, square.1 as i8 + forward).as_u8() {
+                square = next;
+            }
+            square
+        };
+        let open_diagonals = [-1, 1].into_iter()
+            .map(diagonal_end)
+            .filter(|end| end.0.abs_diff(king.0) >= 2)
+            .filter(|end| !pawns.iter().any(|pawn| pawn == end || king.squares_between(end).contains(pawn)))
+            .count();
+        (open_files + open_diagonals) as u32
+    }
+
+    /// Returns the positions of the pawns of `color`, sorted by file and rank
+    fn get_pawn_positions(&self, color: Color) -> Vec<(u8, u8)> {
+        let mut pawns: Vec<(u8, u8)> = self.get_pieces_iter(color)
+            .filter(|piece| piece.get_kind() == PieceKind::Pawn)
+            .map(|piece| *piece.get_position())
+            .collect();
+        pawns.sort();
+        pawns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::king::King;
+    use crate::finished_game::piece::pawn::Pawn;
+    use crate::finished_game::piece::Piece;
+    use crate::square::Square;
+
+    fn create_board() -> Board {
+        Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "e1".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::White, "c2".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::White, "c3".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::White, "h5".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "e8".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::Black, "b7".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::Black, "d6".as_u8().unwrap())),
+        ])
+    }
+
+    #[test]
+    fn c_pawns_are_doubled() {
+        let board = create_board();
+        assert_eq!(board.doubled_pawns(Color::White), vec!["c2".as_u8().unwrap(), "c3".as_u8().unwrap()]);
+        assert_eq!(board.doubled_pawns(Color::Black), vec![]);
+    }
+
+    #[test]
+    fn h_pawn_is_passed() {
+        let board = create_board();
+        assert_eq!(board.passed_pawns(Color::White), vec!["h5".as_u8().unwrap()]);
+        assert_eq!(board.passed_pawns(Color::Black), vec![]);
+    }
+
+    #[test]
+    fn pawns_without_neighbours_are_isolated() {
+        let board = create_board();
+        assert_eq!(board.isolated_pawns(Color::White).len(), 3);
+        assert_eq!(board.isolated_pawns(Color::Black).len(), 2);
+    }
+
+    #[test]
+    fn king_without_pawn_shield_is_exposed() {
+        let castled = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            .....PPP
+            ......K.
+        ").unwrap();
+        let without_g_and_h_pawns = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            .....P..
+            ......K.
+        ").unwrap();
+        assert_eq!(castled.king_exposure(Color::White), 0);
+        assert_eq!(without_g_and_h_pawns.king_exposure(Color::White), 2);
+        assert_eq!(castled.king_exposure(Color::Black), 5);
+    }
+}
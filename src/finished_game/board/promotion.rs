@@ -0,0 +1,86 @@
+use crate::finished_game::board::{Board, Move};
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+
+const PROMOTION_KINDS: [PieceKind; 4] = [PieceKind::Queen, PieceKind::Rook, PieceKind::Bishop, PieceKind::Knight];
+
+impl Board {
+    /// Returns every legal move of `color`, sorted. A pawn move to the last rank is returned once
+    /// for each piece the pawn can become.
+    pub fn legal_moves_with_promotions(&self, color: Color) -> Vec<Move> {
+        let mut moves: Vec<Move> = self.all_legal_moves(color).into_iter()
+            .flat_map(|(from, legal_squares)| legal_squares.into_iter().map(move |to| (from, to)))
+            .flat_map(|(from, to)| self.moves_with_promotions(from, to))
+            .collect();
+        moves.sort();
+        moves
+    }
+
+    /// Returns the move of the piece at `from` to `to`, or one move per promotion piece when it is a pawn
+    /// reaching the last rank
+    pub(crate) fn moves_with_promotions(&self, from: (u8, u8), to: (u8, u8)) -> Vec<Move> {
+        match self.is_promotion(&from, to) {
+            true => PROMOTION_KINDS.iter().map(|&kind| self.create_move(from, to, Some(kind))).collect(),
+            false => vec![self.create_move(from, to, None)],
+        }
+    }
+
+    /// Returns the pawns of `color` that can promote with their next move, sorted
+    pub fn promotion_candidates(&self, color: Color) -> Vec<(u8, u8)> {
+        let mut pawns: Vec<(u8, u8)> = self.all_legal_moves(color).into_iter()
+            .filter(|(from, legal_squares)| legal_squares.iter().any(|&to| self.is_promotion(from, to)))
+            .map(|(from, _)| from)
+            .collect();
+        pawns.sort();
+        pawns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::color::Color;
+    use crate::square::Square;
+
+    #[test]
+    fn pawn_on_seventh_rank_has_four_promotions() {
+        let board = Board::from_ascii("
+            .......k
+            ....P...
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        let pawn_moves: Vec<_> = board.legal_moves_with_promotions(Color::White).into_iter()
+            .filter(|mv| mv.from == "e7".as_u8().unwrap())
+            .collect();
+        assert_eq!(pawn_moves.len(), 4);
+        assert!(pawn_moves.iter().all(|mv| mv.to == "e8".as_u8().unwrap() && mv.promotion.is_some()));
+    }
+
+    #[test]
+    fn start_position_has_no_promotions() {
+        let moves = Board::new().legal_moves_with_promotions(Color::White);
+        assert_eq!(moves.len(), 20);
+        assert!(moves.iter().all(|mv| mv.promotion.is_none()));
+    }
+
+    #[test]
+    fn pawn_on_e7_can_promote() {
+        let board = Board::from_ascii("
+            n......k
+            P...P...
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        assert_eq!(board.promotion_candidates(Color::White), vec!["e7".as_u8().unwrap()]);
+        assert_eq!(board.promotion_candidates(Color::Black), vec![]);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::error::ChessError;
+use crate::finished_game::piece::bishop::Bishop;
+use crate::finished_game::piece::PieceKind;
+use crate::finished_game::piece::rook::Rook;
+
+impl Board {
+    /// Returns the legal squares of the piece at `position` grouped by direction, ordered outwards
+    /// from the piece, so a UI can draw them as arrows. Rooks, bishops and queens get one ray per
+    /// open direction, while every move of other pieces is a ray of its own.
+    pub fn move_rays(&self, position: &(u8, u8)) -> Result<Vec<Vec<(u8, u8)>>, ChessError> {
+        let piece = self.pieces.get(position).ok_or(ChessError::NoPiece(*position))?;
+        let legal_squares = self.legal_squares_of(piece.as_ref());
+        let directions = match piece.get_kind() {
+            PieceKind::Rook => Rook::get_rook_moves(position),
+            PieceKind::Bishop => Bishop::get_bishop_moves(position),
+            PieceKind::Queen => {
+                let mut directions = Rook::get_rook_moves(position);
+                directions.extend(Bishop::get_bishop_moves(position));
+                directions
+            }
+            _ => legal_squares.iter().map(|&square| vec![square]).collect(),
+        };
+
+        let mut rays: Vec<Vec<(u8, u8)>> = directions.into_iter()
+            .map(|direction| direction.into_iter()
+                .take_while(|square| legal_squares.contains(square))
+                .collect::<Vec<_>>())
+            .filter(|ray| !ray.is_empty())
+            .collect();
+        rays.sort();
+        Ok(rays)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::square::Square;
+
+    #[test]
+    fn rook_rays_add_up_to_legal_squares() {
+        let mut board = Board::new();
+        board.do_move("a1", "d4");
+        let position = "d4".as_u8().unwrap();
+        let rays = board.move_rays(&position).unwrap();
+        assert_eq!(rays.len(), 4);
+        assert_eq!(rays.iter().map(|ray| ray.len()).sum::<usize>(), board.get_legal_squares(&position).unwrap().len());
+        assert!(rays.contains(&vec!["d5".as_u8().unwrap(), "d6".as_u8().unwrap(), "d7".as_u8().unwrap()]));
+    }
+
+    #[test]
+    fn knight_rays_are_single_squares() {
+        let board = Board::new();
+        let rays = board.move_rays(&"g1".as_u8().unwrap()).unwrap();
+        assert_eq!(rays, vec![vec!["f3".as_u8().unwrap()], vec!["h3".as_u8().unwrap()]]);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+use crate::square::Square;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl Board {
+    /// Returns the result of the game, or `None` if it is still going on
+    pub fn result(&self) -> Option<GameResult> {
+        if self.is_checkmate(self.active_color) {
+            return match self.active_color {
+                Color::White => Some(GameResult::BlackWins),
+                Color::Black => Some(GameResult::WhiteWins),
+            };
+        }
+        if self.is_draw() {
+            return Some(GameResult::Draw);
+        }
+        None
+    }
+
+    /// Returns true if the game is drawn by stalemate, the fifty-move rule, threefold repetition
+    /// or insufficient material
+    pub fn is_draw(&self) -> bool {
+        self.is_insufficient_material()
+            || self.is_fifty_move_draw()
+            || self.is_threefold_repetition()
+            || self.is_stalemate(self.active_color)
+    }
+
+    /// Returns true if fifty moves by each side have been played without a capture or pawn move
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    /// Returns true if the current position, with the same color to move, has occurred three times
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetitions() >= 3
+    }
+
+    /// Returns true if the player to move may claim a draw by threefold repetition or the fifty-move rule
+    pub fn can_claim_draw(&self) -> bool {
+        self.is_threefold_repetition() || self.is_fifty_move_draw()
+    }
+
+    /// Returns true if the game is drawn without either player claiming it: by stalemate, insufficient
+    /// material, fivefold repetition or seventy-five moves by each side without a capture or pawn move
+    pub fn is_automatic_draw(&self) -> bool {
+        self.is_stalemate(self.active_color)
+            || self.is_insufficient_material()
+            || self.repetitions() >= 5
+            || self.halfmove_clock >= 150
+    }
+
+    /// Returns how many times the current position, with the same color to move, has occurred
+    fn repetitions(&self) -> usize {
+        let current = self.position_history.last().unwrap();
+        self.position_history.iter().filter(|&position| position == current).count()
+    }
+
+    /// Returns true if the position is a known draw with correct play, even if a mate is still possible:
+    /// insufficient material, two knights against a bare king, a single minor piece each, or a rook each.
+    /// Positions with pawns or queens are never counted.
+    pub fn is_theoretical_draw(&self) -> bool {
+        if self.is_insufficient_material() {
+            return true;
+        }
+        let kinds = |color: Color| {
+            let mut kinds: Vec<PieceKind> = self.get_pieces_iter(color)
+                .map(|piece| piece.get_kind())
+                .filter(|&kind| kind != PieceKind::King)
+                .collect();
+            kinds.sort();
+            kinds
+        };
+        let is_minor = |kind: &PieceKind| matches!(kind, PieceKind::Knight | PieceKind::Bishop);
+        let (white, black) = (kinds(Color::White), kinds(Color::Black));
+        match (white.as_slice(), black.as_slice()) {
+            ([PieceKind::Knight, PieceKind::Knight], []) | ([], [PieceKind::Knight, PieceKind::Knight]) => true,
+            ([white_piece], [black_piece]) => (is_minor(white_piece) && is_minor(black_piece))
+                || (*white_piece == PieceKind::Rook && *black_piece == PieceKind::Rook),
+            _ => false,
+        }
+    }
+
+    /// Returns true if neither side can possibly checkmate: only kings are left, together with at
+    /// most a single knight, or bishops that all stand on squares of the same color
+    pub fn is_insufficient_material(&self) -> bool {
+        let others: Vec<_> = self.pieces.values()
+            .filter(|piece| piece.get_kind() != PieceKind::King)
+            .collect();
+        match others.as_slice() {
+            [] => true,
+            [piece] => matches!(piece.get_kind(), PieceKind::Knight | PieceKind::Bishop),
+            pieces => {
+                let first_is_light = pieces[0].get_position().is_light();
+                pieces.iter().all(|piece| {
+                    piece.get_kind() == PieceKind::Bishop && piece.get_position().is_light() == first_is_light
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::board::result::GameResult;
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::bishop::Bishop;
+    use crate::finished_game::piece::king::King;
+    use crate::finished_game::piece::Piece;
+    use crate::square::Square;
+
+    #[test]
+    fn king_against_king_is_draw() {
+        let board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "e1".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "e8".as_u8().unwrap())),
+        ]);
+        assert!(board.is_draw());
+        assert_eq!(board.result(), Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn bishops_on_different_colors_can_mate() {
+        let board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "e1".as_u8().unwrap())),
+            Box::new(Bishop::new(Color::White, "c1".as_u8().unwrap())),
+            Box::new(Bishop::new(Color::White, "f1".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "e8".as_u8().unwrap())),
+        ]);
+        assert!(!board.is_insufficient_material());
+    }
+
+    #[test]
+    fn minor_piece_endgames_are_theoretical_draws() {
+        let draw = |ascii_pieces: &str| {
+            let rows = format!("{}\n........\n........\n........\n........\n........\n........\n....K...", ascii_pieces);
+            Board::from_ascii(&rows).unwrap().is_theoretical_draw()
+        };
+        assert!(draw("....k..B"));
+        assert!(draw("....k..N"));
+        assert!(draw("....k.NN"));
+        assert!(draw("n...k..B"));
+        assert!(draw("r...k..R"));
+        assert!(!draw("....k..Q"));
+        assert!(!draw("....k..R"));
+        assert!(!draw("....kBNN"));
+    }
+
+    #[test]
+    fn threefold_repetition_is_draw() {
+        let mut board = Board::new();
+        assert_eq!(board.result(), None);
+        for _ in 0..2 {
+            board.do_move("g1", "f3");
+            board.do_move("g8", "f6");
+            board.do_move("f3", "g1");
+            board.do_move("f6", "g8");
+        }
+        assert!(board.is_threefold_repetition());
+        assert_eq!(board.result(), Some(GameResult::Draw));
+        assert!(board.can_claim_draw());
+        assert!(!board.is_automatic_draw());
+
+        for _ in 0..2 {
+            board.do_move("g1", "f3");
+            board.do_move("g8", "f6");
+            board.do_move("f3", "g1");
+            board.do_move("f6", "g8");
+        }
+        assert!(board.is_automatic_draw());
+    }
+
+    #[test]
+    fn fools_mate_is_won_by_black() {
+        let mut board = Board::new();
+        board.do_move("f2", "f3");
+        board.do_move("e7", "e5");
+        board.do_move("g2", "g4");
+        board.do_move("d8", "h4");
+        assert_eq!(board.result(), Some(GameResult::BlackWins));
+    }
+}
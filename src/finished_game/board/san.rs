@@ -0,0 +1,312 @@
+use crate::finished_game::board::{Board, Move};
+use crate::finished_game::color::Color;
+use crate::finished_game::error::ChessError;
+use crate::finished_game::piece::{create_piece, PieceKind};
+use crate::square::Square;
+
+/// Why `parse_san` found no move
+#[derive(Debug, PartialEq)]
+pub struct SanError {
+    /// The notation that is not a legal move
+    pub san: String,
+    /// Legal moves written almost the same way, closest first
+    pub suggestions: Vec<String>,
+}
+
+impl Board {
+    /// Returns `mv` in standard algebraic notation, for example `"Nf3"`, `"exd5"`, `"e8=N"` or `"Qxf7#"`.
+    /// A pawn reaching the last rank without a `promotion` is promoted to a queen.
+    pub fn to_san(&self, mv: Move) -> Result<String, ChessError> {
+        let piece = self.pieces.get(&mv.from).ok_or(ChessError::NoPiece(mv.from))?;
+        let color = piece.get_color();
+        let kind = piece.get_kind();
+        let is_capture = self.is_capture(mv.from, mv.to);
+        let file = mv.from.as_string().chars().next().unwrap();
+
+        let mut san = String::new();
+        if self.castling_rook(mv.from, mv.to).is_some() {
+            san.push_str(if mv.to.0 == 6 { "O-O" } else { "O-O-O" });
+        } else {
+            if kind == PieceKind::Pawn {
+                if is_capture {
+                    san.push(file);
+                }
+            } else {
+                san.push(piece.symbol().to_ascii_uppercase());
+                san.push_str(&self.disambiguation(&mv.from, mv.to));
+            }
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&mv.to.as_string());
+        }
+        let promotion = mv.promotion.unwrap_or(PieceKind::Queen);
+        if self.is_promotion(&mv.from, mv.to) {
+            san.push('=');
+            san.push(create_piece(promotion, Color::White, mv.to).symbol());
+        }
+
+        let mut new_board = self.clone();
+        new_board.apply_move_with_promotion(&mv.from, mv.to, promotion)?;
+        if new_board.is_check(color.opposite()) {
+            san.push(if new_board.is_checkmate(color.opposite()) { '#' } else { '+' });
+        }
+        Ok(san)
+    }
+
+    /// Returns every legal move for `color` in standard algebraic notation, sorted. A pawn reaching
+    /// the last rank gives one move for each piece it can become.
+    pub fn legal_moves_san(&self, color: Color) -> Vec<String> {
+        let mut moves: Vec<String> = self.legal_moves_with_promotions(color).into_iter()
+            .filter_map(|mv| self.to_san(mv).ok())
+            .collect();
+        moves.sort();
+        moves.dedup();
+        moves
+    }
+
+    /// Returns every move played on this board in standard algebraic notation, in order. Stops at the first
+    /// move that is not legal when replayed, like a move played out of turn with `apply_move`.
+    pub fn move_list_san(&self) -> Vec<String> {
+        let mut board = self.clone();
+        board.truncate_history(0);
+        self.move_history.iter()
+            .map_while(|undo| {
+                let san = board.to_san(undo.mv).ok()?;
+                board.make_move(undo.mv).ok()?;
+                Some(san)
+            })
+            .collect()
+    }
+
+    /// Returns the moves played as the rows of a score sheet: the move number, the move by white and the
+    /// move by black, if black has moved yet. When the game starts with black to move, the first move by
+    /// white is written as `"..."`.
+    pub fn san_history_grouped(&self) -> Vec<(u32, String, Option<String>)> {
+        let mut start = self.clone();
+        start.truncate_history(0);
+        let mut moves = self.move_list_san().into_iter();
+        let mut rows = Vec::new();
+        if start.active_color == Color::Black {
+            rows.push((start.fullmove_number, String::from("..."), moves.next()));
+        }
+        while let Some(white) = moves.next() {
+            rows.push((start.fullmove_number + rows.len() as u32, white, moves.next()));
+        }
+        rows
+    }
+
+    /// Returns the legal move of `color` written as `san`, with the piece a pawn is promoted to. Check, mate
+    /// and capture marks are optional. If there is no such move, the error suggests up to three legal moves
+    /// that differ by one or two letters.
+    pub fn parse_san(&self, san: &str, color: Color) -> Result<Move, SanError> {
+        let normalise = |san: &str| san.chars().filter(|c| !"+#x!?".contains(*c)).collect::<String>();
+        let normalised = normalise(san);
+        let found = self.legal_moves_with_promotions(color).into_iter()
+            .find(|&mv| self.to_san(mv).is_ok_and(|legal| normalise(&legal) == normalised));
+        if let Some(mv) = found {
+            return Ok(mv);
+        }
+        let mut close: Vec<(usize, String)> = self.legal_moves_san(color).into_iter()
+            .map(|legal| (edit_distance(&normalise(&legal), &normalised), legal))
+            .filter(|(distance, _)| *distance <= 2)
+            .collect();
+        close.sort();
+        Err(SanError { san: san.to_string(), suggestions: close.into_iter().take(3).map(|(_, legal)| legal).collect() })
+    }
+
+    /// Returns the legal moves of the pieces of `color` and `kind` standing on `file`, counted from 0 for
+    /// the a-file, sorted. Resolves input like `Nbd2`.
+    pub fn legal_moves_from_file(&self, color: Color, kind: PieceKind, file: u8) -> Vec<Move> {
+        self.legal_moves_of_kind(color, kind).into_iter().filter(|mv| mv.from.0 == file).collect()
+    }
+
+    /// Returns the legal moves of the pieces of `color` and `kind` standing on `rank`, counted from 0 for
+    /// the first rank, sorted. Resolves input like `R1a3`.
+    pub fn legal_moves_from_rank(&self, color: Color, kind: PieceKind, rank: u8) -> Vec<Move> {
+        self.legal_moves_of_kind(color, kind).into_iter().filter(|mv| mv.from.1 == rank).collect()
+    }
+
+    fn legal_moves_of_kind(&self, color: Color, kind: PieceKind) -> Vec<Move> {
+        self.legal_moves_with_promotions(color).into_iter()
+            .filter(|mv| self.kind_at(mv.from) == Some(kind))
+            .collect()
+    }
+
+    /// Returns the file and/or rank needed to tell the piece at `position` apart from other pieces of
+    /// the same kind that can also move to `target_square`
+    fn disambiguation(&self, position: &(u8, u8), target_square: (u8, u8)) -> String {
+        let Some(piece) = self.pieces.get(position) else { return String::new() };
+        let rivals: Vec<(u8, u8)> = self.get_pieces_iter(piece.get_color())
+            .filter(|other| other.get_kind() == piece.get_kind() && other.get_position() != position)
+            .map(|other| *other.get_position())
+            .filter(|other| self.get_legal_squares(other).is_ok_and(|legal_squares| legal_squares.contains(&target_square)))
+            .collect();
+
+        let square = position.as_string();
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|other| other.0 != position.0) {
+            square[..1].to_string()
+        } else if rivals.iter().all(|other| other.1 != position.1) {
+            square[1..].to_string()
+        } else {
+            square
+        }
+    }
+}
+
+/// Returns how many letters must be added, removed or changed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let change = previous[j] + (a_char != *b_char) as usize;
+            current.push(change.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn three_moves_fill_two_score_sheet_rows() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3")] {
+            board.do_move(position, target);
+        }
+        assert_eq!(board.san_history_grouped(), vec![
+            (1, String::from("e4"), Some(String::from("e5"))),
+            (2, String::from("Nf3"), None),
+        ]);
+    }
+
+    #[test]
+    fn score_sheet_from_black_to_move() {
+        let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 4 30").unwrap();
+        for (position, target) in [("h7", "h6"), ("a1", "a8")] {
+            board.do_move(position, target);
+        }
+        assert_eq!(board.move_list_san(), vec!["h6", "Ra8+"]);
+        assert_eq!(board.san_history_grouped(), vec![
+            (30, String::from("..."), Some(String::from("h6"))),
+            (31, String::from("Ra8+"), None),
+        ]);
+    }
+
+    #[test]
+    fn move_list_stops_at_move_played_out_of_turn() {
+        let mut board = Board::new();
+        board.do_move("e2", "e4");
+        board.apply_move(&"e4".as_u8().unwrap(), "e5".as_u8().unwrap()).unwrap();
+        board.do_move("e7", "e6");
+        assert_eq!(board.move_list_san(), vec!["e4"]);
+    }
+
+    #[test]
+    fn start_position_has_twenty_san_moves() {
+        let board = Board::new();
+        let mut expected: Vec<String> = [
+            "a3", "a4", "b3", "b4", "c3", "c4", "d3", "d4",
+            "e3", "e4", "f3", "f4", "g3", "g4", "h3", "h4",
+            "Na3", "Nc3", "Nf3", "Nh3",
+        ].iter().map(|san| san.to_string()).collect();
+        expected.sort();
+        assert_eq!(board.legal_moves_san(Color::White), expected)
+    }
+
+    #[test]
+    fn parse_san_finds_legal_move() {
+        let board = Board::new();
+        assert_eq!(board.parse_san("Nf3", Color::White), Ok(board.move_from_uci("g1f3").unwrap()));
+        assert!(board.parse_san("Nf4", Color::White).is_err());
+        assert!(board.parse_san("e5", Color::White).is_err());
+    }
+
+    #[test]
+    fn illegal_san_suggests_close_moves() {
+        let error = Board::new().parse_san("Nf4", Color::White).unwrap_err();
+        assert_eq!(error.san, "Nf4");
+        assert_eq!(error.suggestions[0], "Nf3");
+        assert!(error.suggestions.len() <= 3);
+        assert_eq!(Board::new().parse_san("O-O-O", Color::White).unwrap_err().suggestions, Vec::<String>::new());
+    }
+
+    #[test]
+    fn castling_is_written_with_o() {
+        let board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            R...K..R
+        ").unwrap();
+        assert_eq!(board.to_san(board.move_from_uci("e1g1").unwrap()).unwrap(), "O-O");
+        assert_eq!(board.parse_san("O-O-O", Color::White), Ok(board.move_from_uci("e1c1").unwrap()));
+    }
+
+    #[test]
+    fn san_marks_check_and_checkmate() {
+        let mut board = Board::new();
+        board.do_move("f2", "f3");
+        board.do_move("e7", "e5");
+        board.do_move("g2", "g4");
+        assert_eq!(board.to_san(board.move_from_uci("d8h4").unwrap()).unwrap(), "Qh4#");
+        assert_eq!(board.to_san(board.move_from_uci("f8b4").unwrap()).unwrap(), "Bb4");
+    }
+
+    #[test]
+    fn underpromotion_is_written_with_its_piece() {
+        let mut board = Board::from_ascii("
+            ......k.
+            ....P...
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        let sans = board.legal_moves_san(Color::White);
+        assert!(["e8=Q+", "e8=R+", "e8=B", "e8=N"].iter().all(|san| sans.contains(&san.to_string())), "{:?}", sans);
+        assert_eq!(board.to_san(board.move_from_uci("e7e8n").unwrap()).unwrap(), "e8=N");
+        assert_eq!(board.parse_san("e8=N", Color::White), Ok(board.move_from_uci("e7e8n").unwrap()));
+
+        board.make_move(board.move_from_uci("e7e8n").unwrap()).unwrap();
+        assert_eq!(board.move_list_san(), vec!["e8=N"]);
+    }
+
+    #[test]
+    fn knight_moves_filtered_by_file_and_rank() {
+        let board = Board::from_ascii("
+            ....k...
+            ........
+            ........
+            ........
+            ........
+            .....N..
+            ........
+            .N..K...
+        ").unwrap();
+        let d2 = "d2".as_u8().unwrap();
+        let from_b_file = board.legal_moves_from_file(Color::White, PieceKind::Knight, 1);
+        assert!(from_b_file.iter().all(|mv| mv.from == "b1".as_u8().unwrap()));
+        assert_eq!(from_b_file.iter().filter(|mv| mv.to == d2).count(), 1);
+        let from_third_rank = board.legal_moves_from_rank(Color::White, PieceKind::Knight, 2);
+        assert!(from_third_rank.iter().all(|mv| mv.from == "f3".as_u8().unwrap()));
+        assert!(from_third_rank.iter().any(|mv| mv.to == d2));
+        assert_eq!(board.legal_moves_from_file(Color::White, PieceKind::Knight, 0), vec![]);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::finished_game::board::{Board, Move, MoveKind};
+use crate::finished_game::color::Color;
+use crate::finished_game::error::ChessError;
+use crate::finished_game::piece::PieceKind;
+
+impl Board {
+    /// Static exchange evaluation: the material won (or lost, if negative) by the side moving from
+    /// `position` to `target_square` when both sides keep recapturing on that square with their least
+    /// valuable piece for as long as it pays off
+    pub fn see(&self, position: &(u8, u8), target_square: (u8, u8)) -> Result<i32, ChessError> {
+        let mut board = self.clone();
+        let mut color = board.color_at(*position).ok_or(ChessError::NoPiece(*position))?;
+        let mut gains = vec![board.pieces.get(&target_square).map_or(0, |piece| piece.value())];
+        let mut attacker = *position;
+        loop {
+            let captured_value = board.piece_at(attacker).map_or(0, |piece| piece.value());
+            board.apply_move(&attacker, target_square)?;
+            color = color.opposite();
+            let Some(next_attacker) = board.least_valuable_attacker(target_square, color) else { break };
+            let is_king = board.kind_at(next_attacker) == Some(PieceKind::King);
+            if is_king && board.least_valuable_attacker(target_square, color.opposite()).is_some() {
+                break;
+            }
+            gains.push(captured_value - gains.last().unwrap());
+            attacker = next_attacker;
+        }
+        while gains.len() > 1 {
+            let gain = gains.pop().unwrap();
+            let previous = gains.last_mut().unwrap();
+            *previous = -(-*previous).max(gain);
+        }
+        Ok(gains[0])
+    }
+
+    /// Returns the legal moves of `color` that do not hang the moved piece, that is where no capture
+    /// of it afterwards wins material by `see`. Sorted.
+    pub fn non_hanging_moves(&self, color: Color) -> Vec<Move> {
+        self.legal_moves_with_promotions(color).into_iter()
+            .filter(|&mv| {
+                let mut board = self.clone();
+                board.apply_move_with_promotion(&mv.from, mv.to, mv.promotion.unwrap_or(PieceKind::Queen)).is_ok()
+                    && board.attackers_of(mv.to, color.opposite()).into_iter()
+                        .all(|attacker| board.see(&attacker, mv.to).is_ok_and(|gain| gain <= 0))
+            })
+            .collect()
+    }
+
+    /// Returns the pieces of `color` the opponent can win material by taking, that is where some capture
+    /// of the piece has a positive `see` for the opponent. Sorted.
+    pub fn hanging_pieces(&self, color: Color) -> Vec<(u8, u8)> {
+        let mut hanging: Vec<(u8, u8)> = self.get_pieces_iter(color)
+            .map(|piece| *piece.get_position())
+            .filter(|&position| self.attackers_of(position, color.opposite()).into_iter()
+                .any(|attacker| self.see(&attacker, position).is_ok_and(|gain| gain > 0)))
+            .collect();
+        hanging.sort();
+        hanging
+    }
+
+    /// Returns the legal moves of `color` that take a piece, en passant included. Sorted.
+    pub fn capture_moves(&self, color: Color) -> Vec<Move> {
+        self.legal_moves_with_promotions(color).into_iter()
+            .filter(|mv| matches!(mv.kind, MoveKind::Capture | MoveKind::EnPassant))
+            .collect()
+    }
+
+    /// Returns true if the side to move has no captures and is not in check, so a quiescence search
+    /// can stop here
+    pub fn is_quiet(&self) -> bool {
+        !self.is_check(self.active_color) && self.capture_moves(self.active_color).is_empty()
+    }
+
+    fn least_valuable_attacker(&self, square: (u8, u8), color: Color) -> Option<(u8, u8)> {
+        self.attackers_of(square, color).into_iter()
+            .min_by_key(|attacker| match self.pieces[attacker].get_kind() {
+                PieceKind::King => i32::MAX,
+                _ => self.pieces[attacker].value(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::{Board, Move, MoveKind};
+    use crate::finished_game::color::Color;
+    use crate::finished_game::piece::king::King;
+    use crate::finished_game::piece::pawn::Pawn;
+    use crate::finished_game::piece::Piece;
+    use crate::finished_game::piece::queen::Queen;
+    use crate::finished_game::piece::rook::Rook;
+    use crate::square::Square;
+
+    fn create_board(defended: bool) -> Board {
+        let mut pieces: Vec<Box<dyn Piece>> = vec![
+            Box::new(King::new(Color::White, "e1".as_u8().unwrap())),
+            Box::new(Queen::new(Color::White, "d1".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "e8".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::Black, "d5".as_u8().unwrap())),
+        ];
+        if defended {
+            pieces.push(Box::new(Pawn::new(Color::Black, "e6".as_u8().unwrap())));
+        }
+        Board::from_pieces(pieces)
+    }
+
+    #[test]
+    fn queen_takes_pawn_defended_by_pawn() {
+        let board = create_board(true);
+        assert_eq!(board.see(&"d1".as_u8().unwrap(), "d5".as_u8().unwrap()), Ok(-8))
+    }
+
+    #[test]
+    fn queen_takes_undefended_pawn() {
+        let board = create_board(false);
+        assert_eq!(board.see(&"d1".as_u8().unwrap(), "d5".as_u8().unwrap()), Ok(1))
+    }
+
+    #[test]
+    fn rook_backed_by_queen_wins_the_exchange() {
+        let board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "g1".as_u8().unwrap())),
+            Box::new(Rook::new(Color::White, "d2".as_u8().unwrap())),
+            Box::new(Queen::new(Color::White, "d1".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "g8".as_u8().unwrap())),
+            Box::new(Rook::new(Color::Black, "d5".as_u8().unwrap())),
+            Box::new(Rook::new(Color::Black, "d8".as_u8().unwrap())),
+        ]);
+        assert_eq!(board.see(&"d2".as_u8().unwrap(), "d5".as_u8().unwrap()), Ok(5))
+    }
+
+    #[test]
+    fn queen_hanging_to_knight_is_not_a_safe_move() {
+        let board = Board::from_ascii("
+            ....k...
+            ........
+            .....n..
+            ........
+            .b......
+            ........
+            .P.P....
+            .N.QK...
+        ").unwrap();
+        let moves = board.non_hanging_moves(Color::White);
+        let mv = |from: &str, to: &str| Move { from: from.as_u8().unwrap(), to: to.as_u8().unwrap(), promotion: None, kind: MoveKind::Quiet };
+        assert!(!moves.contains(&mv("d1", "g4")));
+        assert!(moves.contains(&mv("b1", "c3")));
+    }
+
+    #[test]
+    fn start_position_is_quiet() {
+        let mut board = Board::new();
+        assert!(board.is_quiet());
+        board.do_move("e2", "e4");
+        board.do_move("d7", "d5");
+        assert!(!board.is_quiet());
+        assert_eq!(board.capture_moves(Color::White), vec![Move { from: "e4".as_u8().unwrap(), to: "d5".as_u8().unwrap(), promotion: None, kind: MoveKind::Capture }]);
+    }
+
+    #[test]
+    fn bishop_attacked_by_pawn_is_hanging() {
+        let board = Board::from_ascii("
+            ....k...
+            ....p...
+            ...p....
+            ..B.....
+            ........
+            ........
+            .....PPP
+            ......K.
+        ").unwrap();
+        assert_eq!(board.hanging_pieces(Color::White), vec!["c5".as_u8().unwrap()]);
+        assert_eq!(board.hanging_pieces(Color::Black), vec![]);
+    }
+}
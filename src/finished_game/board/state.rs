@@ -0,0 +1,78 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::finished_game::board::{Board, Move};
+
+impl Board {
+    /// Writes the board to the file at `path` as the FEN of the position before the first move, followed
+    /// by every move played in UCI notation. Loading replays the moves, so the clocks, castling rights,
+    /// en passant square and the history used for repetitions all come back as they were.
+    /// The crate has no serde dependency, and the boxed pieces could not derive it, so the file is plain text.
+    pub fn save_state(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut start = self.clone();
+        start.truncate_history(0);
+        let moves: Vec<String> = self.move_history().into_iter().map(Move::to_uci).collect();
+        fs::write(path, format!("{}\n{}\n", start.to_fen(), moves.join(" ")))
+    }
+
+    /// Reads a board saved with `save_state` from the file at `path`
+    pub fn load_state(path: impl AsRef<Path>) -> io::Result<Board> {
+        let state = fs::read_to_string(path)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut lines = state.lines();
+        let fen = lines.next().unwrap_or_default();
+        let mut board = Board::from_fen(fen).map_err(|_| invalid(format!("Ugyldig stilling i fila: {}", fen)))?;
+        for uci in lines.next().unwrap_or_default().split_whitespace() {
+            let mv = board.move_from_uci(uci).ok_or_else(|| invalid(format!("Ugyldig trekk i fila: {}", uci)))?;
+            board.make_move(mv).map_err(|_| invalid(format!("Ulovleg trekk i fila: {}", uci)))?;
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use crate::finished_game::board::{Board, Move, MoveKind};
+    use crate::finished_game::piece::PieceKind;
+    use crate::square::Square;
+
+    #[test]
+    fn saved_state_keeps_repetitions() {
+        let mut board = Board::new();
+        for (position, target) in [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")].repeat(2) {
+            board.do_move(position, target);
+        }
+        let path = env::temp_dir().join("rust_chess_workshop_state_test.txt");
+        board.save_state(&path).unwrap();
+        let loaded = Board::load_state(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.to_fen(), board.to_fen());
+        assert_eq!(loaded.move_history(), board.move_history());
+        assert_eq!(loaded.position_history, board.position_history);
+        assert_eq!(loaded.result(), board.result());
+        assert!(loaded.is_threefold_repetition());
+    }
+
+    #[test]
+    fn uci_promotion_round_trip() {
+        let board = Board::from_ascii("
+            .......k
+            P.......
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        let mv = Move { from: "a7".as_u8().unwrap(), to: "a8".as_u8().unwrap(), promotion: Some(PieceKind::Knight), kind: MoveKind::Quiet };
+        assert_eq!(mv.to_uci(), "a7a8n");
+        assert_eq!(board.move_from_uci("a7a8n"), Some(mv));
+        assert_eq!(board.move_from_uci("a7a8k"), None);
+    }
+}
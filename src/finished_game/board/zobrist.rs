@@ -0,0 +1,56 @@
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::Piece;
+
+/// SplitMix64, used to derive a fixed pseudo random key for each piece on each square
+fn split_mix(index: u64) -> u64 {
+    let mut z = index.wrapping_add(1).wrapping_mul(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn zobrist_key(piece: &dyn Piece) -> u64 {
+    let (x, y) = *piece.get_position();
+    let piece_index = piece.get_kind() as u64 * 2 + piece.get_color() as u64;
+    split_mix(piece_index * 64 + y as u64 * 8 + x as u64)
+}
+
+impl Board {
+    /// Zobrist hash of the piece placement, the color to move, the castling rights and the en passant file. Equal positions give equal hashes.
+    pub fn zobrist_hash(&self) -> u64 {
+        let mut hash = self.pieces.values().fold(0, |hash, piece| hash ^ zobrist_key(piece.as_ref()));
+        if self.active_color == Color::Black {
+            hash ^= split_mix(12 * 64);
+        }
+        let rights = self.castling_rights;
+        for (i, allowed) in [rights.white_king_side, rights.white_queen_side, rights.black_king_side, rights.black_queen_side].into_iter().enumerate() {
+            if allowed {
+                hash ^= split_mix(12 * 64 + 1 + i as u64);
+            }
+        }
+        if let Some((file, _)) = self.en_passant {
+            hash ^= split_mix(12 * 64 + 5 + file as u64);
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+
+    #[test]
+    fn same_placement_gives_same_hash() {
+        let mut board = Board::new();
+        let mut other = Board::new();
+        board.do_move("g1", "f3");
+        board.do_move("b8", "c6");
+        board.do_move("b1", "c3");
+        other.do_move("b1", "c3");
+        other.do_move("b8", "c6");
+        other.do_move("g1", "f3");
+        assert_eq!(board.zobrist_hash(), other.zobrist_hash());
+        assert_ne!(board.zobrist_hash(), Board::new().zobrist_hash());
+    }
+}
@@ -0,0 +1,542 @@
+use std::time::{Duration, Instant};
+
+use crate::finished_game::board::{Board, Move};
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+
+mod kqk;
+
+/// Score of a checkmate in centipawns. Mates found closer to the root score higher.
+pub const MATE: i32 = 100_000;
+
+/// Evaluation in centipawns from which a side counts as clearly winning
+pub const WINNING: i32 = 300;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TerminalKind {
+    Checkmate,
+    Stalemate,
+}
+
+/// Returns how the game has ended if `color` has no legal moves, or `None` if the game goes on
+pub fn is_terminal(board: &Board, color: Color) -> Option<TerminalKind> {
+    if !board.all_legal_moves(color).is_empty() {
+        None
+    } else if board.is_check(color) {
+        Some(TerminalKind::Checkmate)
+    } else {
+        Some(TerminalKind::Stalemate)
+    }
+}
+
+/// Static evaluation in centipawns, positive when white is better
+pub fn evaluate(board: &Board) -> i32 {
+    100 * (board.material(Color::White) - board.material(Color::Black))
+}
+
+/// Returns the best move for `color` found by searching `depth` half moves ahead,
+/// or `None` if there are no legal moves. With king and queen against king it plays `kqk_best_move`.
+pub fn best_move(board: &Board, color: Color, depth: u8) -> Option<Move> {
+    kqk_best_move(board, color).or_else(|| search_depth(board, color, depth, None).ok().flatten().map(|(line, _)| line[0]))
+}
+
+/// Same as `best_move`, but when `color` is clearly winning it skips moves that let the opponent claim
+/// a draw by threefold repetition or the fifty-move rule, unless there are no other moves
+pub fn best_move_avoiding_draws(board: &Board, color: Color, depth: u8) -> Option<Move> {
+    if board.side_relative_eval(color) < WINNING {
+        return best_move(board, color, depth);
+    }
+    let progress: Vec<Move> = moves(board, color).into_iter()
+        .filter(|&mv| after(board, mv).is_some_and(|new_board| !new_board.can_claim_draw()))
+        .collect();
+    match search_moves(board, color, progress, depth, None) {
+        Ok(Some((line, _))) => Some(line[0]),
+        _ => best_move(board, color, depth),
+    }
+}
+
+/// Returns the best move for `color` found by searching one half move deeper at a time until
+/// `max_time` has passed, or `None` if there are no legal moves. The first depth is always completed,
+/// and the search stops early once a forced mate is found.
+pub fn search(board: &Board, color: Color, max_time: Duration) -> Option<Move> {
+    principal_variation(board, color, max_time).first().copied()
+}
+
+/// Same as `search`, but returns the whole line the engine expects: the best move followed by
+/// the best replies of both sides
+pub fn principal_variation(board: &Board, color: Color, max_time: Duration) -> Vec<Move> {
+    let deadline = Instant::now() + max_time;
+    let Ok(Some((mut line, mut score))) = search_depth(board, color, 1, None) else { return Vec::new() };
+    for depth in 2..=u8::MAX {
+        if score.abs() >= MATE - u8::MAX as i32 {
+            break;
+        }
+        match search_depth(board, color, depth, Some(deadline)) {
+            Ok(Some((new_line, new_score))) => (line, score) = (new_line, new_score),
+            _ => break,
+        }
+    }
+    line
+}
+
+/// Same as `best_move`, but picks at random among the moves, giving a move more weight the closer its score
+/// is to the best score. `temperature` is in centipawns: a move `temperature` worse than the best is picked
+/// e times less often. At temperature 0 this is `best_move`. `rng` is the state of the random numbers.
+pub fn best_move_with_temperature(board: &Board, color: Color, depth: u8, temperature: f64, rng: &mut u64) -> Option<Move> {
+    if temperature <= 0.0 {
+        return best_move(board, color, depth);
+    }
+    let scores: Vec<(Move, i32)> = moves(board, color).into_iter()
+        .filter_map(|mv| {
+            let new_board = after(board, mv)?;
+            let (score, _) = negamax(&new_board, color.opposite(), depth.saturating_sub(1), 1, -MATE - 1, MATE + 1, None).ok()?;
+            Some((mv, -score))
+        })
+        .collect();
+    let best_score = scores.iter().map(|&(_, score)| score).max()?;
+    let weights: Vec<f64> = scores.iter()
+        .map(|&(_, score)| ((score - best_score) as f64 / temperature).exp())
+        .collect();
+    let mut pick = next_random(rng) as f64 / u64::MAX as f64 * weights.iter().sum::<f64>();
+    for (&(mv, _), weight) in scores.iter().zip(weights) {
+        if pick < weight {
+            return Some(mv);
+        }
+        pick -= weight;
+    }
+    scores.last().map(|&(mv, _)| mv)
+}
+
+/// Returned by the search when the deadline has passed before it was done
+struct OutOfTime;
+
+/// Returns the principal variation and its score, or `None` if there are no legal moves
+fn search_depth(board: &Board, color: Color, depth: u8, deadline: Option<Instant>) -> Result<Option<(Vec<Move>, i32)>, OutOfTime> {
+    search_moves(board, color, moves(board, color), depth, deadline)
+}
+
+/// Same as `search_depth`, but only tries the moves in `moves` at the root
+fn search_moves(board: &Board, color: Color, moves: Vec<Move>, depth: u8, deadline: Option<Instant>) -> Result<Option<(Vec<Move>, i32)>, OutOfTime> {
+    let mut best = None;
+    let mut alpha = -MATE - 1;
+    for mv in moves {
+        let Some(new_board) = after(board, mv) else { continue };
+        let (score, line) = negamax(&new_board, color.opposite(), depth.saturating_sub(1), 1, -MATE - 1, -alpha, deadline)?;
+        if -score > alpha {
+            alpha = -score;
+            best = Some(([vec![mv], line].concat(), alpha));
+        }
+    }
+    Ok(best)
+}
+
+/// Returns the score for `color` together with the best line found from this position
+fn negamax(board: &Board, color: Color, depth: u8, ply: i32, mut alpha: i32, beta: i32, deadline: Option<Instant>) -> Result<(i32, Vec<Move>), OutOfTime> {
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        return Err(OutOfTime);
+    }
+    let moves = moves(board, color);
+    if moves.is_empty() {
+        let score = match board.is_check(color) {
+            true => -(MATE - ply),
+            false => 0,
+        };
+        return Ok((score, Vec::new()));
+    }
+    if depth == 0 {
+        return Ok((board.side_relative_eval(color), Vec::new()));
+    }
+    let mut best_line = Vec::new();
+    for mv in moves {
+        let Some(new_board) = after(board, mv) else { continue };
+        let (score, line) = negamax(&new_board, color.opposite(), depth - 1, ply + 1, -beta, -alpha, deadline)?;
+        if -score >= beta {
+            return Ok((beta, Vec::new()));
+        }
+        if -score > alpha {
+            alpha = -score;
+            best_line = [vec![mv], line].concat();
+        }
+    }
+    Ok((alpha, best_line))
+}
+
+/// Returns the number of moves in the shortest forced mate for `color`, up to `max_n` moves, and the first
+/// move of it. Unlike `best_move` this only looks for mates, so it finds them deeper for the same time.
+pub fn mate_in(board: &Board, color: Color, max_n: u8) -> Option<(u8, Move)> {
+    (1..=max_n).find_map(|n| mating_move(board, color, n).map(|mv| (n, mv)))
+}
+
+/// Returns the first move of `color` that mates within `n` moves whatever the opponent answers
+fn mating_move(board: &Board, color: Color, n: u8) -> Option<Move> {
+    moves(board, color).into_iter().find(|&mv| {
+        let Some(new_board) = after(board, mv) else { return false };
+        match is_terminal(&new_board, color.opposite()) {
+            Some(terminal) => terminal == TerminalKind::Checkmate,
+            None => n > 1 && moves(&new_board, color.opposite()).into_iter().all(|reply| {
+                after(&new_board, reply).is_some_and(|reply_board| mating_move(&reply_board, color, n - 1).is_some())
+            }),
+        }
+    })
+}
+
+/// Returns the moves of `color` that leave the opponent stalemated, sorted. For the losing side
+/// these are the moves that save a draw.
+pub fn find_stalemate_saves(board: &Board, color: Color) -> Vec<Move> {
+    moves(board, color).into_iter()
+        .filter(|&mv| after(board, mv).is_some_and(|new_board| is_terminal(&new_board, color.opposite()) == Some(TerminalKind::Stalemate)))
+        .collect()
+}
+
+/// Returns the move for `strong` that mates the lone king of the opponent fastest with king and queen,
+/// or `None` if the board is not king and queen against king. Never takes more than ten moves.
+pub fn kqk_best_move(board: &Board, strong: Color) -> Option<Move> {
+    kqk::plies_to_mate(board, strong)?;
+    moves(board, strong).into_iter()
+        .filter_map(|mv| Some((kqk::plies_to_mate(&after(board, mv)?, strong)?, mv)))
+        .min_by_key(|&(plies, _)| plies)
+        .map(|(_, best)| best)
+}
+
+/// Returns each legal first move of `color` in UCI notation, like `"e2e4"` or `"a7a8n"`, with the number
+/// of positions `depth` half moves deep that follow it. Comparing the counts with a known good engine
+/// shows which move the move generation goes wrong after.
+pub fn perft_divide(board: &Board, color: Color, depth: u8) -> Vec<(String, u64)> {
+    board.legal_moves_with_promotions(color).into_iter()
+        .filter_map(|mv| Some((mv.to_uci(), perft(&after(board, mv)?, color.opposite(), depth.saturating_sub(1)))))
+        .collect()
+}
+
+/// Counts the positions reached after every sequence of `depth` legal half moves, starting with `color`
+fn perft(board: &Board, color: Color, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    board.legal_moves_with_promotions(color).into_iter()
+        .filter_map(|mv| Some(perft(&after(board, mv)?, color.opposite(), depth - 1)))
+        .sum()
+}
+
+/// Counts the games that end in checkmate or stalemate within `depth` half moves, starting with `color`
+/// to move. Like `perft_divide`, but positions still going on when the depth runs out are not counted.
+pub fn count_games(board: &Board, color: Color, depth: u8) -> u64 {
+    if is_terminal(board, color).is_some() {
+        return 1;
+    }
+    if depth == 0 {
+        return 0;
+    }
+    board.legal_moves_with_promotions(color).into_iter()
+        .filter_map(|mv| Some(count_games(&after(board, mv)?, color.opposite(), depth - 1)))
+        .sum()
+}
+
+/// Returns the average number of legal replies to each legal move of `color`, or 0 when `color` has no
+/// moves. Puzzles where the opponent has many replies to read through are harder.
+pub fn branching_factor(board: &Board, color: Color) -> f64 {
+    let moves = moves(board, color);
+    if moves.is_empty() {
+        return 0.0;
+    }
+    let replies: usize = moves.iter()
+        .filter_map(|&mv| after(board, mv))
+        .map(|new_board| new_board.legal_moves_with_promotions(color.opposite()).len())
+        .sum();
+    replies as f64 / moves.len() as f64
+}
+
+/// Plays random legal moves from the start position until the game is over and returns the final board.
+/// The same `seed` always gives the same game.
+#[cfg(test)]
+pub fn random_game(seed: u64) -> Board {
+    play_random_game(seed, |_, _| {})
+}
+
+/// Same as `random_game`, but calls `inspect` with the board and the chosen move before each move
+#[cfg(test)]
+fn play_random_game(seed: u64, mut inspect: impl FnMut(&Board, Move)) -> Board {
+    let mut board = Board::new();
+    let mut state = seed;
+    while board.result().is_none() {
+        let moves = moves(&board, board.get_active_color());
+        let mv = moves[(next_random(&mut state) % moves.len() as u64) as usize];
+        inspect(&board, mv);
+        if board.make_move(mv).is_err() {
+            break;
+        }
+    }
+    board
+}
+
+/// Advances `state` and returns the next pseudo random number (SplitMix64)
+pub(crate) fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Returns the legal moves of `color` in a fixed order, so the search is repeatable
+fn moves(board: &Board, color: Color) -> Vec<Move> {
+    board.legal_moves_with_promotions(color)
+}
+
+/// Returns a copy of `board` with `mv` played, or `None` if there is no piece to move
+fn after(board: &Board, mv: Move) -> Option<Board> {
+    let mut new_board = board.clone();
+    new_board.apply_move_with_promotion(&mv.from, mv.to, mv.promotion.unwrap_or(PieceKind::Queen)).ok()?;
+    Some(new_board)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::{Board, Move};
+    use crate::finished_game::color::Color;
+    use std::time::Duration;
+
+    use crate::finished_game::engine::{best_move, branching_factor, count_games, mate_in, best_move_avoiding_draws, best_move_with_temperature, find_stalemate_saves, is_terminal, kqk_best_move, moves, next_random, perft_divide, play_random_game, principal_variation, random_game, search, TerminalKind};
+    use crate::finished_game::piece::king::King;
+    use crate::finished_game::piece::pawn::Pawn;
+    use crate::finished_game::piece::Piece;
+    use crate::finished_game::piece::queen::Queen;
+    use crate::finished_game::piece::rook::Rook;
+    use crate::square::Square;
+
+    /// White is in check and can either take the queen, stalemating black, or step into Qc1#
+    fn create_stalemate_or_mate_board() -> Board {
+        Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "d1".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::White, "e2".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::White, "f2".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "a1".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::Black, "a2".as_u8().unwrap())),
+            Box::new(Queen::new(Color::Black, "c2".as_u8().unwrap())),
+        ])
+    }
+
+    #[test]
+    fn start_position_is_not_terminal() {
+        assert_eq!(is_terminal(&Board::new(), Color::White), None)
+    }
+
+    #[test]
+    fn terminal_kinds() {
+        let mut board = create_stalemate_or_mate_board();
+        board.do_move("d1", "c2");
+        assert_eq!(is_terminal(&board, Color::Black), Some(TerminalKind::Stalemate));
+
+        let mut board = create_stalemate_or_mate_board();
+        board.do_move("d1", "e1");
+        board.do_move("c2", "c1");
+        assert_eq!(is_terminal(&board, Color::White), Some(TerminalKind::Checkmate));
+    }
+
+    #[test]
+    fn engine_prefers_stalemate_to_being_mated() {
+        let board = create_stalemate_or_mate_board();
+        assert_eq!(best_move(&board, Color::White, 2), board.move_from_uci("d1c2"))
+    }
+
+    #[test]
+    fn temperature_zero_is_best_move() {
+        let mut rng = 1;
+        let board = create_stalemate_or_mate_board();
+        assert_eq!(best_move_with_temperature(&board, Color::White, 2, 0.0, &mut rng), best_move(&board, Color::White, 2));
+        assert_eq!(rng, 1);
+    }
+
+    #[test]
+    fn temperature_gives_other_moves() {
+        let board = Board::new();
+        let best = best_move(&board, Color::White, 1);
+        let mut rng = 7;
+        let picked: Vec<_> = (0..10).map(|_| best_move_with_temperature(&board, Color::White, 1, 50.0, &mut rng).unwrap()).collect();
+        assert!(picked.iter().any(|&mv| Some(mv) != best));
+        assert!(picked.iter().all(|mv| moves(&board, Color::White).contains(mv)));
+    }
+
+    #[test]
+    fn winning_side_avoids_repetition() {
+        let mut board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "d1".as_u8().unwrap())),
+            Box::new(Queen::new(Color::White, "a1".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::White, "h2".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "h6".as_u8().unwrap())),
+        ]);
+        for (position, target) in [("a1", "a3"), ("h6", "h5"), ("a3", "a1"), ("h5", "h6")].repeat(2) {
+            board.do_move(position, target);
+        }
+        board.do_move("a1", "a3");
+        board.do_move("h6", "h5");
+
+        let repetition = board.move_from_uci("a3a1").unwrap();
+        assert_eq!(best_move(&board, Color::White, 2), Some(repetition));
+        let best = best_move_avoiding_draws(&board, Color::White, 2).unwrap();
+        assert_ne!(best, repetition);
+        board.make_move(best).unwrap();
+        assert!(!board.can_claim_draw());
+    }
+
+    #[test]
+    fn count_games_finds_the_mate_in_one() {
+        let mut board = create_stalemate_or_mate_board();
+        board.do_move("d1", "e1");
+        assert_eq!(count_games(&board, Color::Black, 0), 0);
+        assert_eq!(count_games(&board, Color::Black, 1), 1);
+        assert_eq!(count_games(&Board::new(), Color::White, 2), 0);
+    }
+
+    #[test]
+    fn branching_factor_of_start_position() {
+        let factor = branching_factor(&Board::new(), Color::White);
+        assert!(factor.is_finite() && factor > 0.0);
+        assert_eq!(factor, 20.0);
+    }
+
+    #[test]
+    fn rook_ladder_is_mate_in_two() {
+        let board = Board::from_ascii("
+            .......k
+            ........
+            ........
+            ........
+            ........
+            ........
+            .R......
+            R...K...
+        ").unwrap();
+        assert_eq!(mate_in(&board, Color::White, 1), None);
+        let (n, mv) = mate_in(&board, Color::White, 3).unwrap();
+        assert_eq!(n, 2);
+
+        let mut board = board.clone();
+        board.make_move(mv).unwrap();
+        for reply in moves(&board, Color::Black) {
+            let mut reply_board = board.clone();
+            reply_board.make_move(reply).unwrap();
+            assert_eq!(mate_in(&reply_board, Color::White, 1).map(|(n, _)| n), Some(1));
+        }
+    }
+
+    #[test]
+    fn taking_the_queen_is_a_stalemate_save() {
+        let board = create_stalemate_or_mate_board();
+        assert_eq!(find_stalemate_saves(&board, Color::White), vec![board.move_from_uci("d1c2").unwrap()]);
+        assert_eq!(find_stalemate_saves(&Board::new(), Color::White), vec![]);
+    }
+
+    #[test]
+    fn engine_finds_mate_in_one() {
+        let mut board = create_stalemate_or_mate_board();
+        board.do_move("d1", "e1");
+        assert_eq!(best_move(&board, Color::Black, 2), board.move_from_uci("c2c1"))
+    }
+
+    #[test]
+    fn random_games_keep_board_invariants() {
+        for seed in [1, 2, 3, 42] {
+            let board = play_random_game(seed, |board, mv| {
+                board.assert_consistent();
+                let ascii = board.to_ascii();
+                let legal_moves = board.all_legal_moves(board.get_active_color());
+                assert!(legal_moves.values().flatten().all(Square::on_board), "{}", ascii);
+                assert!(legal_moves[&mv.from].contains(&mv.to), "{}", ascii);
+
+                let mut after = board.clone();
+                after.make_move(mv).unwrap();
+                after.assert_consistent();
+                after.undo_move();
+                after.assert_consistent();
+                assert_eq!(&after, board);
+            });
+            assert!(board.result().is_some());
+        }
+    }
+
+    #[test]
+    fn random_game_is_repeatable() {
+        assert_eq!(random_game(7).to_fen(), random_game(7).to_fen());
+    }
+
+    #[test]
+    fn perft_divide_from_start_position() {
+        let divide = perft_divide(&Board::new(), Color::White, 2);
+        assert_eq!(divide.len(), 20);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 400);
+        assert!(divide.contains(&(String::from("e2e4"), 20)));
+    }
+
+    #[test]
+    fn search_with_tiny_budget_returns_legal_move() {
+        let board = Board::new();
+        let mv = search(&board, Color::White, Duration::from_millis(1)).unwrap();
+        assert!(board.get_legal_squares(&mv.from).unwrap().contains(&mv.to));
+    }
+
+    #[test]
+    fn search_finds_mate_in_two() {
+        let board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "g1".as_u8().unwrap())),
+            Box::new(Rook::new(Color::White, "b1".as_u8().unwrap())),
+            Box::new(Rook::new(Color::White, "b2".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "h8".as_u8().unwrap())),
+            Box::new(Rook::new(Color::Black, "a8".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::Black, "g7".as_u8().unwrap())),
+            Box::new(Pawn::new(Color::Black, "h7".as_u8().unwrap())),
+        ]);
+        assert_eq!(search(&board, Color::White, Duration::from_secs(60)), board.move_from_uci("b2b8"));
+        let line: Vec<String> = principal_variation(&board, Color::White, Duration::from_secs(60)).into_iter()
+            .map(Move::to_uci)
+            .collect();
+        assert_eq!(line, vec!["b2b8", "a8b8", "b1b8"]);
+    }
+
+    /// Returns a random position with white to move where white has king and queen against king
+    fn random_kqk_board(state: &mut u64) -> Board {
+        loop {
+            let squares = [0; 3].map(|_| ((next_random(state) % 8) as u8, (next_random(state) % 8) as u8));
+            let board = Board::from_pieces(vec![
+                Box::new(King::new(Color::White, squares[0])),
+                Box::new(Queen::new(Color::White, squares[1])),
+                Box::new(King::new(Color::Black, squares[2])),
+            ]);
+            let kings_touch = squares[0].0.abs_diff(squares[2].0) <= 1 && squares[0].1.abs_diff(squares[2].1) <= 1;
+            if !kings_touch && squares[1] != squares[0] && squares[1] != squares[2] && !board.is_check(Color::Black) {
+                return board;
+            }
+        }
+    }
+
+    #[test]
+    fn kqk_driver_mates_from_random_positions() {
+        let mut state = 3;
+        for _ in 0..10 {
+            let mut board = random_kqk_board(&mut state);
+            let start = board.to_fen();
+            for _ in 0..50 {
+                let mv = kqk_best_move(&board, Color::White).unwrap();
+                board.make_move(mv).unwrap();
+                if board.result().is_some() {
+                    break;
+                }
+                let replies = moves(&board, Color::Black);
+                let reply = replies[(next_random(&mut state) % replies.len() as u64) as usize];
+                board.make_move(reply).unwrap();
+            }
+            assert!(board.is_checkmate(Color::Black), "{}", start);
+        }
+    }
+
+    #[test]
+    fn best_move_plays_the_kqk_driver() {
+        let mut state = 5;
+        let board = random_kqk_board(&mut state);
+        assert_eq!(best_move(&board, Color::White, 1), kqk_best_move(&board, Color::White));
+    }
+
+    #[test]
+    fn kqk_driver_needs_king_and_queen_against_king() {
+        assert_eq!(kqk_best_move(&Board::new(), Color::White), None);
+    }
+}
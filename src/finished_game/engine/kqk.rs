@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::PieceKind;
+use crate::square::{BOARD_SIZE, KING_OFFSETS, Square};
+
+/// The squares of the strong king, the queen and the lone king
+type Position = ((u8, u8), (u8, u8), (u8, u8));
+
+static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+
+/// Half moves to mate for every king and queen against king position, solved backwards from the checkmates
+struct Tablebase {
+    /// With the strong side to move, `None` for positions that can not occur
+    strong_to_move: Vec<Option<u8>>,
+    /// With the lone king to move, `None` when it can hold a draw by stalemate or by taking the queen
+    weak_to_move: Vec<Option<u8>>,
+}
+
+/// Returns how many half moves `strong` needs to mate with king and queen against king, with the color
+/// to move on the board, or `None` if the board is not king and queen against king or the lone king draws
+pub fn plies_to_mate(board: &Board, strong: Color) -> Option<u8> {
+    let position = kqk_position(board, strong)?;
+    let tablebase = TABLEBASE.get_or_init(Tablebase::build);
+    match board.get_active_color() == strong {
+        true => tablebase.strong_to_move[index(position)],
+        false => tablebase.weak_to_move[index(position)],
+    }
+}
+
+/// Returns the position if `strong` has exactly a king and a queen and the opponent only a king
+fn kqk_position(board: &Board, strong: Color) -> Option<Position> {
+    let mut king = None;
+    let mut queen = None;
+    let mut lone_king = None;
+    for square in (0..BOARD_SIZE).flat_map(|y| (0..BOARD_SIZE).map(move |x| (x, y))) {
+        let Some(piece) = board.piece_at(square) else { continue };
+        let slot = match (piece.get_color() == strong, piece.get_kind()) {
+            (true, PieceKind::King) => &mut king,
+            (true, PieceKind::Queen) => &mut queen,
+            (false, PieceKind::King) => &mut lone_king,
+            _ => return None,
+        };
+        if slot.replace(square).is_some() {
+            return None;
+        }
+    }
+    Some((king?, queen?, lone_king?))
+}
+
+impl Tablebase {
+    /// Retrograde analysis: every position is reached backwards from the positions one half move closer
+    /// to mate, so the first value found for a position is the fastest mate
+    fn build() -> Tablebase {
+        let mut tablebase = Tablebase {
+            strong_to_move: vec![None; 64 * 64 * 64],
+            weak_to_move: vec![None; 64 * 64 * 64],
+        };
+        // How many moves of the lone king are not yet known to lose, per position with the lone king to move
+        let mut escapes = vec![0; 64 * 64 * 64];
+        // Solved positions in the order they were found, with whether the strong side is to move
+        let mut solved = VecDeque::new();
+        for position in all_positions() {
+            escapes[index(position)] = weak_moves(position).len() + can_take_queen(position) as usize;
+            if escapes[index(position)] == 0 && is_check(position) {
+                tablebase.weak_to_move[index(position)] = Some(0);
+                solved.push_back((position, false));
+            }
+        }
+        while let Some((position, strong_to_move)) = solved.pop_front() {
+            match strong_to_move {
+                false => {
+                    let plies = tablebase.weak_to_move[index(position)].unwrap() + 1;
+                    for previous in strong_unmoves(position) {
+                        if !is_check(previous) && tablebase.strong_to_move[index(previous)].is_none() {
+                            tablebase.strong_to_move[index(previous)] = Some(plies);
+                            solved.push_back((previous, true));
+                        }
+                    }
+                }
+                true => {
+                    let plies = tablebase.strong_to_move[index(position)].unwrap() + 1;
+                    for previous in weak_unmoves(position) {
+                        escapes[index(previous)] -= 1;
+                        if escapes[index(previous)] == 0 {
+                            tablebase.weak_to_move[index(previous)] = Some(plies);
+                            solved.push_back((previous, false));
+                        }
+                    }
+                }
+            }
+        }
+        tablebase
+    }
+}
+
+fn index((king, queen, lone_king): Position) -> usize {
+    let square = |(x, y): (u8, u8)| (y * 8 + x) as usize;
+    square(king) * 64 * 64 + square(queen) * 64 + square(lone_king)
+}
+
+/// Returns every position where the three pieces stand on different squares and the kings are apart
+fn all_positions() -> impl Iterator<Item=Position> {
+    let squares = || (0..BOARD_SIZE).flat_map(|y| (0..BOARD_SIZE).map(move |x| (x, y)));
+    squares()
+        .flat_map(move |king| squares().flat_map(move |queen| squares().map(move |lone_king| (king, queen, lone_king))))
+        .filter(|&(king, queen, lone_king)| king != queen && queen != lone_king && !touches(king, lone_king) && king != lone_king)
+}
+
+/// Returns true if the squares are next to each other
+fn touches(a: (u8, u8), b: (u8, u8)) -> bool {
+    a != b && a.0.abs_diff(b.0) <= 1 && a.1.abs_diff(b.1) <= 1
+}
+
+fn step((x, y): (u8, u8), (dx, dy): (i8, i8)) -> Option<(u8, u8)> {
+    (x as i8 + dx, y as i8 + dy).as_u8()
+}
+
+/// Returns the squares the queen on `queen` reaches, up to and including the first of the `blockers`
+fn queen_rays(queen: (u8, u8), blockers: &[(u8, u8)]) -> Vec<(u8, u8)> {
+    let mut squares = Vec::new();
+    for direction in KING_OFFSETS {
+        let mut square = queen;
+        while let Some(next) = step(square, direction) {
+            squares.push(next);
+            if blockers.contains(&next) {
+                break;
+            }
+            square = next;
+        }
+    }
+    squares
+}
+
+/// Returns true if the queen on `queen` attacks `target` on a line that `blocker` does not stand on
+fn queen_attacks(queen: (u8, u8), target: (u8, u8), blocker: (u8, u8)) -> bool {
+    let (dx, dy) = (target.0 as i8 - queen.0 as i8, target.1 as i8 - queen.1 as i8);
+    if queen == target || dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+        return false;
+    }
+    let direction = (dx.signum(), dy.signum());
+    let mut square = queen;
+    while let Some(next) = step(square, direction) {
+        if next == target {
+            return true;
+        }
+        if next == blocker {
+            return false;
+        }
+        square = next;
+    }
+    false
+}
+
+fn is_check((king, queen, lone_king): Position) -> bool {
+    queen_attacks(queen, lone_king, king)
+}
+
+/// Returns true if the lone king can take the queen because the strong king does not guard it
+fn can_take_queen((king, queen, lone_king): Position) -> bool {
+    touches(queen, lone_king) && !touches(queen, king)
+}
+
+/// Returns the positions after each move of the lone king, not counting taking the queen
+fn weak_moves((king, queen, lone_king): Position) -> Vec<Position> {
+    lone_king.neighbors()
+        .filter(|&square| square != king && square != queen && !touches(square, king) && !queen_attacks(queen, square, king))
+        .map(|square| (king, queen, square))
+        .collect()
+}
+
+/// Returns the positions with the lone king to move where one move of the lone king leads to `position`
+fn weak_unmoves((king, queen, lone_king): Position) -> Vec<Position> {
+    lone_king.neighbors()
+        .filter(|&square| square != king && square != queen && !touches(square, king))
+        .map(|square| (king, queen, square))
+        .collect()
+}
+
+/// Returns the positions with the strong side to move where one move of the king or the queen leads to `position`
+fn strong_unmoves((king, queen, lone_king): Position) -> Vec<Position> {
+    let king_unmoves = king.neighbors()
+        .filter(|&square| square != queen && square != lone_king && !touches(square, lone_king))
+        .map(|square| (square, queen, lone_king));
+    let queen_unmoves = queen_rays(queen, &[king, lone_king]).into_iter()
+        .filter(|&square| square != king && square != lone_king)
+        .map(|square| (king, square, lone_king));
+    king_unmoves.chain(queen_unmoves).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::board::Board;
+    use crate::finished_game::color::Color;
+    use crate::finished_game::engine::kqk::{plies_to_mate, Tablebase, TABLEBASE};
+    use crate::finished_game::piece::king::King;
+    use crate::finished_game::piece::Piece;
+    use crate::finished_game::piece::queen::Queen;
+    use crate::finished_game::piece::rook::Rook;
+    use crate::square::Square;
+
+    #[test]
+    fn longest_mate_is_ten_moves() {
+        let tablebase = TABLEBASE.get_or_init(Tablebase::build);
+        assert_eq!(tablebase.strong_to_move.iter().flatten().max(), Some(&19));
+    }
+
+    #[test]
+    fn plies_to_mate_with_either_color_to_move() {
+        let board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "b6".as_u8().unwrap())),
+            Box::new(Queen::new(Color::White, "h7".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "b8".as_u8().unwrap())),
+        ]);
+        assert_eq!(plies_to_mate(&board, Color::White), Some(1));
+
+        let mut board = board.clone();
+        board.do_move("h7", "c8");
+        assert_eq!(plies_to_mate(&board, Color::White), None);
+    }
+
+    #[test]
+    fn plies_to_mate_only_for_king_and_queen_against_king() {
+        assert_eq!(plies_to_mate(&Board::new(), Color::White), None);
+        let board = Board::from_pieces(vec![
+            Box::new(King::new(Color::White, "b6".as_u8().unwrap())),
+            Box::new(Rook::new(Color::White, "h7".as_u8().unwrap())),
+            Box::new(King::new(Color::Black, "b8".as_u8().unwrap())),
+        ]);
+        assert_eq!(plies_to_mate(&board, Color::White), None);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::finished_game::color::Color;
+
+/// Why a board function could not answer for the position it was given
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChessError {
+    /// There is no piece on the square
+    NoPiece((u8, u8)),
+    /// The board has no king of the color
+    MissingKing(Color),
+}
+
+impl ChessError {
+    /// The message shown to the player
+    pub fn message(&self) -> &'static str {
+        match self {
+            ChessError::NoPiece(_) => "Det er inga brikke i feltet du valde",
+            ChessError::MissingKing(_) => "Det er ingen konge av denne fargen på brettet",
+        }
+    }
+}
@@ -1,28 +1,103 @@
 use std::collections::HashSet;
 use std::io;
 use std::io::{BufRead, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::finished_game::board::Board;
+use crate::finished_game::board::{Board, GameResult, Move};
 use crate::finished_game::color::Color;
+use crate::finished_game::engine;
+use crate::finished_game::piece::PieceKind;
 use crate::square::Square;
 
 struct Game {
     board: Board,
     turn: Color,
     finished: bool,
+    result: Option<GameResult>,
+    /// The color the engine plays and how many half moves it searches, when playing against the engine
+    engine: Option<(Color, u8)>,
+    /// Print an evaluation bar after every move, turned on and off with `eval`
+    show_evaluation: bool,
+    /// State of the random numbers the engine uses to vary its moves
+    rng: u64,
+}
+
+/// How far in centipawns from the best move the engine strays per difficulty level below the highest
+const TEMPERATURE_PER_LEVEL: f64 = 20.0;
+
+/// Returns a seed for the random numbers that differs between runs
+fn random_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(1, |time| time.as_nanos() as u64)
+}
+
+/// The number of squares in the evaluation bar
+const EVALUATION_BAR_WIDTH: i32 = 8;
+
+/// Returns an evaluation bar like `"[#####---] +1.5"` for `score` in centipawns. The bar fills up
+/// toward white from +10 and empties toward black from -10.
+fn evaluation_bar(score: i32) -> String {
+    let filled = ((score.clamp(-1000, 1000) + 1000) * EVALUATION_BAR_WIDTH + 1000) / 2000;
+    format!(
+        "[{}{}] {:+.1}",
+        "#".repeat(filled as usize),
+        "-".repeat((EVALUATION_BAR_WIDTH - filled) as usize),
+        score as f64 / 100.0
+    )
 }
 
 impl Game {
     fn new() -> Self {
-        Game { board: Board::new(), turn: Color::White, finished: false }
+        Game { board: Board::new(), turn: Color::White, finished: false, result: None, engine: None, show_evaluation: false, rng: 1 }
+    }
+
+    /// A game where the engine plays the opposite color of `player`. Difficulty 1 to 5 is the search depth.
+    /// Below difficulty 5 the engine also picks among near-best moves at random, so games differ.
+    fn against_engine(player: Color, difficulty: u8) -> Self {
+        Game { engine: Some((player.opposite(), difficulty.clamp(1, 5))), rng: random_seed(), ..Game::new() }
+    }
+
+    /// A game from a random Chess960 starting position, different each run
+    fn chess960() -> Self {
+        let mut rng = random_seed();
+        Game { board: Board::new_960_random(&mut rng), rng, ..Game::new() }
+    }
+
+    /// Returns the engine's move. When it is clearly winning it plays the best move that does not let the
+    /// opponent claim a draw, otherwise a near-best move at random for the lower difficulties.
+    fn engine_move(&mut self, color: Color, depth: u8) -> Option<Move> {
+        match self.board.side_relative_eval(color) >= engine::WINNING {
+            true => engine::best_move_avoiding_draws(&self.board, color, depth),
+            false => engine::best_move_with_temperature(&self.board, color, depth, TEMPERATURE_PER_LEVEL * (5 - depth) as f64, &mut self.rng),
+        }
     }
 
     fn play(&mut self, input: &mut impl BufRead) {
         self.board.print(None);
         self.print_turn();
         loop {
+            if let Some((engine_color, depth)) = self.engine.filter(|&(engine_color, _)| engine_color == self.turn) {
+                let Some(mv) = self.engine_move(engine_color, depth) else {
+                    println!("{} har ingen lovlege trekk.", engine_color.print_capitalised());
+                    break;
+                };
+                println!("Motstandaren spelte {}", self.board.to_san(mv).unwrap_or_default());
+                if self.board.make_move(mv).is_err() {
+                    break;
+                }
+                self.finish_move();
+                if self.finished {
+                    break;
+                }
+                continue;
+            }
             let Some(position) = self.get_piece(input) else { break; };
-            let legal_squares = self.board.get_legal_squares(&position);
+            let legal_squares = match self.board.get_legal_squares(&position) {
+                Ok(legal_squares) => legal_squares,
+                Err(error) => {
+                    println!("{}", error.message());
+                    continue;
+                }
+            };
             if legal_squares.is_empty() {
                 println!("Inga lovlege trekk for denne brikka!");
                 continue;
@@ -31,25 +106,57 @@ impl Game {
 
             // maybe change this to normal if else block?
             let Some(position_to_move_to) = self.get_move(&position, legal_squares, input) else { break };
-            match position_to_move_to {
-                position_to_move_to if position_to_move_to == position => {
-                    println!("Du satte brikka tilbake.");
-                    self.board.print(None);
-                    continue
-                }
+            if position_to_move_to == position {
+                println!("Du satte brikka tilbake.");
+                self.board.print(None);
+                continue
+            }
+            let promotion = match self.board.is_promotion(&position, position_to_move_to) {
+                true => self.get_promotion(input),
+                false => PieceKind::Queen,
+            };
+            let moved = match position_to_move_to {
                 position_to_move_to if self.board.get_square_color(&position_to_move_to) == Some(self.turn.opposite()) => {
-                    self.board.capture(&position, position_to_move_to);
+                    self.board.capture(&position, position_to_move_to, promotion)
                 }
                 position_to_move_to => {
-                    self.board.move_piece(&position, position_to_move_to);
+                    self.board.apply_move_with_promotion(&position, position_to_move_to, promotion).map(|_| ())
                 }
+            };
+            if let Err(error) = moved {
+                println!("{}", error.message());
+                continue;
             }
+            self.finish_move();
+        }
+    }
 
-            self.board.print(None);
-            self.next_turn();
-            self.print_turn();
-            self.warn_check();
+    /// Shows the board after a move and hands the turn over
+    fn finish_move(&mut self) {
+        self.board.print(None);
+        println!("{}", self.captured_material());
+        if self.show_evaluation {
+            println!("{}", evaluation_bar(engine::evaluate(&self.board)));
         }
+        self.next_turn();
+        self.print_turn();
+        self.warn_check();
+        self.check_draw();
+    }
+
+    /// Ends the game on an automatic draw, or tells the player to move that a draw can be claimed
+    fn check_draw(&mut self) {
+        if self.board.is_automatic_draw() {
+            println!("Partiet enda remis.");
+            self.end_in_draw();
+        } else if self.board.can_claim_draw() {
+            println!("Du kan krevje remis ved å skrive claim.");
+        }
+    }
+
+    fn end_in_draw(&mut self) {
+        self.result = Some(GameResult::Draw);
+        self.exit_game();
     }
 
     fn next_turn(&mut self) {
@@ -60,6 +167,63 @@ impl Game {
         println!("{} sin tur", self.turn.print_capitalised())
     }
 
+    /// Returns the pieces each side has captured and who is ahead in captured material,
+    /// for example `"Kvit har fanga: ♞ | Svart har fanga: - | Kvit +3"`
+    fn captured_material(&self) -> String {
+        let captured = |color: Color| self.board.captured_pieces(color);
+        let symbols = |color: Color| match captured(color).is_empty() {
+            true => String::from("-"),
+            false => captured(color).iter().map(|piece| piece.print().to_string()).collect::<Vec<_>>().join(" "),
+        };
+        let value = |color: Color| captured(color).iter().map(|piece| piece.value()).sum::<i32>();
+        let difference = value(Color::Black) - value(Color::White);
+        let score = match difference {
+            0 => String::from("Likt"),
+            difference if difference > 0 => format!("{} +{}", Color::White.print_capitalised(), difference),
+            difference => format!("{} +{}", Color::Black.print_capitalised(), -difference),
+        };
+        format!("Kvit har fanga: {} | Svart har fanga: {} | {}", symbols(Color::Black), symbols(Color::White), score)
+    }
+
+    /// Returns the line the engine expects from here in standard algebraic notation,
+    /// for example `"Forslag: e4 e5 Nf3"`
+    fn hint(&self) -> String {
+        let mut board = self.board.clone();
+        let mut line = Vec::new();
+        for mv in engine::principal_variation(&self.board, self.turn, Duration::from_secs(1)) {
+            let Ok(san) = board.to_san(mv) else { break };
+            line.push(san);
+            if board.make_move(mv).is_err() {
+                break;
+            }
+        }
+        match line.is_empty() {
+            true => String::from("Inga forslag, det finst ingen lovlege trekk"),
+            false => format!("Forslag: {}", line.join(" ")),
+        }
+    }
+
+    /// Saves the game to the file at `path`, so it can be resumed with `last`
+    fn save(&self, path: &str) {
+        match self.board.save_state(path) {
+            Ok(()) => println!("Partiet er lagra i {}", path),
+            Err(error) => println!("Kunne ikkje lagre partiet: {}", error),
+        }
+    }
+
+    /// Resumes the game saved in the file at `path`, with the color to move in the saved position
+    fn load(&mut self, path: &str) {
+        match Board::load_state(path) {
+            Ok(board) => {
+                self.board = board;
+                self.turn = self.board.get_active_color();
+                self.board.print(None);
+                self.print_turn();
+            }
+            Err(error) => println!("Kunne ikkje laste partiet: {}", error),
+        }
+    }
+
     fn warn_check(&self) {
         if self.board.is_check(self.turn) {
             println!("{} konge står i sjakk!", self.turn.print_capitalised());
@@ -68,7 +232,7 @@ impl Game {
 
     fn get_piece(&mut self, input: &mut impl BufRead) -> Option<(u8, u8)> {
         while !self.finished {
-            print!("Vel ei brikke å flytte: ");
+            print!("Vel ei brikke å flytte (? for forslag): ");
             io::stdout().flush().unwrap();
             if let Some(position) = self.select_square(input) {
                 match self.board.get_square_color(&position) {
@@ -97,8 +261,9 @@ impl Game {
                 Some(square) if legal_squares.contains(&square) => {
                     return Some(square)
                 },
-                Some(_) => {
-                    println!("Feltet du valte er ikkje lov å flytte til!")
+                Some(square) => {
+                    let reason = self.board.explain_illegal(position, square);
+                    println!("{}", reason.map_or("Feltet du valte er ikkje lov å flytte til!", |reason| reason.message()))
                 },
                 _ => continue
             }
@@ -106,17 +271,65 @@ impl Game {
         None
     }
 
+    /// Ask which piece a promoted pawn should become. Empty or unknown input gives a queen.
+    fn get_promotion(&mut self, input: &mut impl BufRead) -> PieceKind {
+        print!("Vel kva bonden skal bli (Q/R/B/N, standard Q): ");
+        io::stdout().flush().unwrap();
+        let mut choice = String::new();
+        input.read_line(&mut choice).unwrap();
+        match choice.trim().to_ascii_uppercase().as_str() {
+            "R" => PieceKind::Rook,
+            "B" => PieceKind::Bishop,
+            "N" => PieceKind::Knight,
+            _ => PieceKind::Queen,
+        }
+    }
+
     /// Read chess square name from stdin and return position
     /// For example `"a8" -> (0, 0)`
     fn select_square(&mut self, input: &mut impl BufRead) -> Option<(u8, u8)> {
         let mut square = String::new();
         input.read_line(&mut square).unwrap();
+        if let Some(path) = square.trim().strip_prefix("lagre ") {
+            self.save(path.trim());
+            return None
+        }
+        if let Some(path) = square.trim().strip_prefix("last ") {
+            self.load(path.trim());
+            return None
+        }
         square.retain(|c| !c.is_ascii_whitespace());
 
         if square == "x" {
             self.exit_game();
             return None
         }
+        if square == "claim" {
+            match self.board.can_claim_draw() {
+                true => {
+                    println!("Du kravde remis.");
+                    self.end_in_draw();
+                }
+                false => println!("Du kan ikkje krevje remis no."),
+            }
+            return None
+        }
+        if square == "?" {
+            println!("{}", self.hint());
+            return None
+        }
+        if square == "eval" {
+            self.show_evaluation = !self.show_evaluation;
+            match self.show_evaluation {
+                true => println!("{}", evaluation_bar(engine::evaluate(&self.board))),
+                false => println!("Evalueringa er slått av."),
+            }
+            return None
+        }
+        if square == "koord" {
+            self.board.print_with_indices();
+            return None
+        }
 
         square.as_str().as_u8()
     }
@@ -131,8 +344,22 @@ pub fn main() {
     game.play(&mut io::stdin().lock());
 }
 
+/// Plays a game against the engine, with `player` moving by input and the engine on the other side
+pub fn play_vs_engine(player: Color, difficulty: u8) {
+    let mut game = Game::against_engine(player, difficulty);
+    game.play(&mut io::stdin().lock());
+}
+
+/// Plays a game between two players from a random Chess960 starting position
+pub fn play_960() {
+    let mut game = Game::chess960();
+    game.play(&mut io::stdin().lock());
+}
+
 #[cfg(test)]
 mod tests {
+    use std::env;
+    use std::fs;
     use std::io::BufReader;
 
     use super::*;
@@ -145,4 +372,109 @@ mod tests {
 
         game.play(&mut input);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn promote_pawn_to_knight() {
+        let board = Board::from_ascii("
+            ........
+            P......k
+            ........
+            ........
+            ........
+            ........
+            ........
+            K.......
+        ").unwrap();
+        let mut game = Game { board, turn: Color::White, finished: false, result: None, engine: None, show_evaluation: false, rng: 1 };
+        let input_data = "a7\na8\nN\nx\n".as_bytes();
+        let mut input = BufReader::new(input_data);
+
+        game.play(&mut input);
+        let diagram = game.board.to_ascii();
+        assert!(diagram.starts_with("N......."), "{}", diagram);
+    }
+
+    #[test]
+    fn threefold_repetition_can_be_claimed() {
+        let mut game = Game::new();
+        let input_data = "g1\nf3\ng8\nf6\nf3\ng1\nf6\ng8\n".repeat(2) + "claim\n";
+        let mut input = BufReader::new(input_data.as_bytes());
+
+        game.play(&mut input);
+        assert_eq!(game.result, Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn engine_answers_every_move_with_a_legal_move() {
+        let mut game = Game::against_engine(Color::White, 1);
+        let input_data = "e2\ne4\nd2\nd4\ng1\nf3\nx\n".as_bytes();
+        let mut input = BufReader::new(input_data);
+
+        game.play(&mut input);
+        let history = game.board.move_history();
+        assert_eq!(history.len(), 6);
+        let mut board = Board::new();
+        for mv in history {
+            assert_eq!(board.make_move(mv), Ok(()));
+        }
+    }
+
+    #[test]
+    fn evaluation_bar_leans_toward_the_side_ahead() {
+        assert_eq!(evaluation_bar(0), "[####----] +0.0");
+        assert_eq!(evaluation_bar(900), "[########] +9.0");
+        assert_eq!(evaluation_bar(-150), "[###-----] -1.5");
+    }
+
+    #[test]
+    fn eval_command_turns_the_bar_on() {
+        let mut game = Game::new();
+        let input_data = "eval
+e2
+e4
+x
+".as_bytes();
+        let mut input = BufReader::new(input_data);
+
+        game.play(&mut input);
+        assert!(game.show_evaluation);
+        assert_eq!(game.board.move_history().len(), 1);
+    }
+
+    #[test]
+    fn coordinate_overlay_does_not_use_up_the_turn() {
+        let mut game = Game::new();
+        let input_data = "koord\ne2\ne4\nx\n".as_bytes();
+        let mut input = BufReader::new(input_data);
+
+        game.play(&mut input);
+        assert_eq!(game.board.move_history().len(), 1);
+    }
+
+    #[test]
+    fn saved_game_is_resumed_with_black_to_move() {
+        let path = env::temp_dir().join("rust_chess_workshop_game_test.txt");
+        let mut game = Game::new();
+        let input_data = format!("e2\ne4\nlagre {}\nx\n", path.display());
+        game.play(&mut BufReader::new(input_data.as_bytes()));
+
+        let mut resumed = Game::new();
+        let input_data = format!("last {}\ne7\ne5\nx\n", path.display());
+        resumed.play(&mut BufReader::new(input_data.as_bytes()));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.board.move_history().len(), 2);
+        assert_eq!(resumed.turn, Color::White);
+    }
+
+    #[test]
+    fn captured_knight_gives_white_three() {
+        let mut board = Board::new();
+        for (position, target) in [("e2", "e4"), ("g8", "f6"), ("d2", "d3"), ("f6", "d5"), ("e4", "d5")] {
+            board.do_move(position, target);
+        }
+        let game = Game { board, turn: Color::Black, finished: false, result: None, engine: None, show_evaluation: false, rng: 1 };
+        assert!(game.captured_material().ends_with("Kvit +3"), "{}", game.captured_material());
+        assert!(Game::new().captured_material().ends_with("Likt"));
+    }
+}
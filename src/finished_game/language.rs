@@ -0,0 +1,7 @@
+/// The language used for piece names and messages
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Language {
+    #[default]
+    Norwegian,
+    English,
+}
@@ -1,9 +1,68 @@
+use std::io;
+use std::time::Duration;
+
+use crate::finished_game::pgn::PgnError;
+
 pub(crate) mod piece;
-mod board;
+pub(crate) mod board;
 mod game;
 pub mod color;
+pub mod engine;
+pub mod error;
+pub mod language;
+pub mod pgn;
+pub mod record;
+pub mod ruleset;
 
 pub fn main() {
     println!("Kjører game::main() i finished_game");
     game::main()
-}
\ No newline at end of file
+}
+
+/// Plays against the engine from the start position. `difficulty` from 1 to 5 is how many half moves the engine looks ahead.
+pub fn play_vs_engine(player: color::Color, difficulty: u8) {
+    game::play_vs_engine(player, difficulty)
+}
+
+/// Plays a game between two players from a random Chess960 starting position
+pub fn play_960() {
+    game::play_960()
+}
+
+/// Prints every first move from the start position with the number of positions `depth` half moves deep after it
+pub fn print_perft_divide(depth: u8) {
+    let divide = engine::perft_divide(&board::Board::new(), color::Color::White, depth);
+    for (mv, nodes) in &divide {
+        println!("{}: {}", mv, nodes);
+    }
+    println!("Totalt: {}", divide.iter().map(|(_, nodes)| nodes).sum::<u64>());
+}
+
+/// Prints the game in the PGN `movetext` move by move, waiting `delay` between each move
+pub fn watch_pgn(movetext: &str, delay: Duration) -> Result<(), PgnError> {
+    pgn::watch(movetext, delay, &mut io::stdout()).map(|_| ())
+}
+
+/// Prints what the engine finds in the position written as `fen`, starting with the best move after searching for a second
+pub fn print_analysis(fen: &str) {
+    let Ok(board) = board::Board::from_fen(fen) else {
+        println!("Ugyldig stilling: {}", fen);
+        return;
+    };
+    let color = board.get_active_color();
+    match engine::search(&board, color, Duration::from_secs(1)) {
+        Some(mv) => println!("Beste trekk: {}", board.to_san(mv).unwrap_or_default()),
+        None => println!("{} har ingen lovlege trekk", color.print_capitalised()),
+    }
+    println!("Parti som endar med matt eller patt innan 2 halvtrekk: {}", engine::count_games(&board, color, 2));
+    println!("Forgreiningsfaktor: {:.1}", engine::branching_factor(&board, color));
+    if let Some((n, mv)) = engine::mate_in(&board, color, 2) {
+        println!("Matt i {}: {}", n, board.to_san(mv).unwrap_or_default());
+    }
+    let stalemate_saves: Vec<String> = engine::find_stalemate_saves(&board, color).into_iter()
+        .filter_map(|mv| board.to_san(mv).ok())
+        .collect();
+    if !stalemate_saves.is_empty() {
+        println!("Trekk som gir patt: {}", stalemate_saves.join(", "));
+    }
+}
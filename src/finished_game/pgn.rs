@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+use std::io;
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+use crate::finished_game::board::{Board, Move};
+
+#[derive(Debug, PartialEq)]
+pub enum PgnError {
+    /// The move is not legal, or is written in notation the board does not support
+    IllegalMove(String),
+    /// The position in the FEN tag can not be read
+    InvalidFen(String),
+    /// Writing the positions to the output failed
+    Io(io::ErrorKind),
+}
+
+impl PgnError {
+    /// The message shown to the player
+    pub fn message(&self) -> String {
+        match self {
+            PgnError::IllegalMove(san) => format!("Ulovleg trekk: {}", san),
+            PgnError::InvalidFen(fen) => format!("Ugyldig stilling: {}", fen),
+            PgnError::Io(kind) => format!("Kunne ikkje skrive ut stillinga: {}", kind),
+        }
+    }
+}
+
+/// Returns the position the game in `pgn` starts from: the one in the `[FEN "..."]` tag if there is one,
+/// otherwise the normal start position
+pub fn start_position(pgn: &str) -> Result<Board, PgnError> {
+    let fen = pgn.lines()
+        .filter_map(|line| line.trim().strip_prefix("[FEN ")?.strip_suffix(']'))
+        .map(|value| value.trim().trim_matches('"'))
+        .next();
+    match fen {
+        Some(fen) => Board::from_fen(fen).map_err(|_| PgnError::InvalidFen(fen.to_string())),
+        None => Ok(Board::new()),
+    }
+}
+
+/// Returns the moves of a PGN movetext like `"1. e4 e5 2. Nf3 {comment} Nc6 1-0"`, promotions included.
+/// The moves are played from `start_position`. Other tag pairs, comments, NAGs like `$1`, move numbers and the
+/// result are skipped.
+pub fn read_movetext(movetext: &str) -> Result<Vec<Move>, PgnError> {
+    let mut board = start_position(movetext)?;
+    let mut color = board.get_active_color();
+    let mut moves = Vec::new();
+    for san in tokens(movetext) {
+        let mv = board.parse_san(&san, color).map_err(|_| PgnError::IllegalMove(san.clone()))?;
+        board.make_move(mv).map_err(|_| PgnError::IllegalMove(san))?;
+        moves.push(mv);
+        color = color.opposite();
+    }
+    Ok(moves)
+}
+
+/// Writes each position of the game in `movetext` to `output` with the last move highlighted,
+/// waiting `delay` between moves. With no delay only the final position is written.
+/// Fails with `PgnError::Io` if `output` can not be written to.
+pub fn watch(movetext: &str, delay: Duration, output: &mut impl Write) -> Result<Board, PgnError> {
+    let moves = read_movetext(movetext)?;
+    let mut board = start_position(movetext)?;
+    if !delay.is_zero() {
+        write!(output, "{}", board.render(None)).map_err(|error| PgnError::Io(error.kind()))?;
+    }
+    for (i, &mv) in moves.iter().enumerate() {
+        board.make_move(mv).map_err(|_| PgnError::IllegalMove(mv.to_uci()))?;
+        if !delay.is_zero() || i == moves.len() - 1 {
+            if !delay.is_zero() {
+                thread::sleep(delay);
+            }
+            let last_move = HashSet::from([mv.from, mv.to]);
+            write!(output, "{}", board.render(Some(&last_move))).map_err(|error| PgnError::Io(error.kind()))?;
+        }
+    }
+    Ok(board)
+}
+
+fn tokens(movetext: &str) -> Vec<String> {
+    let mut without_comments = String::new();
+    let mut depth = 0;
+    for line in movetext.lines().filter(|line| !line.trim_start().starts_with('[')) {
+        for c in line.split(';').next().unwrap().chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                c if depth == 0 => without_comments.push(c),
+                _ => {}
+            }
+        }
+        without_comments.push(' ');
+    }
+    without_comments.split_whitespace()
+        .filter(|token| !["1-0", "0-1", "1/2-1/2", "*"].contains(token) && !token.starts_with('$'))
+        .map(|token| token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.'))
+        .filter(|token| !token.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::io;
+    use std::time::Duration;
+
+    use crate::finished_game::board::Board;
+    use crate::finished_game::pgn::{PgnError, read_movetext, start_position, watch};
+    use crate::square::Square;
+
+    const SCHOLARS_MATE: &str = "1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 {?? } 4. Qxf7# 1-0";
+
+    #[test]
+    fn read_scholars_mate() {
+        let moves = read_movetext(SCHOLARS_MATE).unwrap();
+        assert_eq!(moves.len(), 7);
+        assert_eq!((moves[6].from, moves[6].to), ("h5".as_u8().unwrap(), "f7".as_u8().unwrap()));
+    }
+
+    #[test]
+    fn illegal_move_is_rejected() {
+        assert_eq!(read_movetext("1. e4 e4"), Err(PgnError::IllegalMove(String::from("e4"))));
+    }
+
+    #[test]
+    fn watch_without_delay_prints_final_position() {
+        let mut expected = Board::new();
+        for (position, target) in [("e2", "e4"), ("e7", "e5"), ("d1", "h5"), ("b8", "c6"), ("f1", "c4"), ("g8", "f6"), ("h5", "f7")] {
+            expected.do_move(position, target);
+        }
+        let last_move = HashSet::from(["h5".as_u8().unwrap(), "f7".as_u8().unwrap()]);
+
+        let mut output = Vec::new();
+        watch(SCHOLARS_MATE, Duration::ZERO, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected.render(Some(&last_move)))
+    }
+
+    #[test]
+    fn replay_starts_from_fen_tag() {
+        let pgn = "[Event \"Puzzle\"]\n[SetUp \"1\"]\n[FEN \"6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 4 30\"]\n\n30... h6 31. Ra8+ Kh7 *";
+        assert_eq!(start_position(pgn).unwrap().to_fen(), "6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 4 30");
+        let board = watch(pgn, Duration::ZERO, &mut Vec::new()).unwrap();
+        assert_eq!(board.to_fen(), "R7/5ppk/7p/8/8/8/5PPP/6K1 w - - 2 32");
+    }
+
+    #[test]
+    fn failed_write_is_returned() {
+        let mut output = [0u8; 16];
+        let error = watch(SCHOLARS_MATE, Duration::ZERO, &mut &mut output[..]).unwrap_err();
+        assert_eq!(error, PgnError::Io(io::ErrorKind::WriteZero));
+    }
+
+    #[test]
+    fn bad_fen_tag_is_rejected() {
+        assert_eq!(read_movetext("[FEN \"8/8 w - -\"]\n1. e4"), Err(PgnError::InvalidFen(String::from("8/8 w - -"))));
+    }
+}
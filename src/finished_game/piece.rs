@@ -3,6 +3,13 @@ use std::collections::HashSet;
 use dyn_clonable::clonable;
 
 use crate::finished_game::color::Color;
+use crate::finished_game::language::Language;
+use crate::finished_game::piece::bishop::Bishop;
+use crate::finished_game::piece::king::King;
+use crate::finished_game::piece::knight::Knight;
+use crate::finished_game::piece::pawn::Pawn;
+use crate::finished_game::piece::queen::Queen;
+use crate::finished_game::piece::rook::Rook;
 
 pub mod pawn;
 pub mod rook;
@@ -11,13 +18,157 @@ pub mod bishop;
 pub mod queen;
 pub mod king;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
 #[clonable]
 pub trait Piece: Clone {
     fn new(color: Color, position: (u8, u8)) -> Self where Self: Sized;
     fn print(&self) -> char;
     fn get_name(&self) -> String;
+    fn get_kind(&self) -> PieceKind;
     fn get_color(&self) -> Color;
     fn get_position(&self) -> &(u8, u8);
     fn move_piece(&mut self, target: (u8, u8));
     fn get_moves(&self, team: &HashSet<(u8, u8)>, rival_team: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)>;
+
+    /// Material value of the piece counted in pawns. The king can't be traded and is worth nothing.
+    fn value(&self) -> i32 {
+        match self.get_kind() {
+            PieceKind::Pawn => 1,
+            PieceKind::Knight | PieceKind::Bishop => 3,
+            PieceKind::Rook => 5,
+            PieceKind::Queen => 9,
+            PieceKind::King => 0,
+        }
+    }
+
+    /// The name of the piece in `language`
+    fn name_in(&self, language: Language) -> String {
+        match language {
+            Language::Norwegian => self.get_name(),
+            Language::English => String::from(match self.get_kind() {
+                PieceKind::Pawn => "pawn",
+                PieceKind::Knight => "knight",
+                PieceKind::Bishop => "bishop",
+                PieceKind::Rook => "rook",
+                PieceKind::Queen => "queen",
+                PieceKind::King => "king",
+            }),
+        }
+    }
+
+    /// The letter of the piece in FEN, upper case for white and lower case for black
+    fn symbol(&self) -> char {
+        let letter = match self.get_kind() {
+            PieceKind::Pawn => 'p',
+            PieceKind::Knight => 'n',
+            PieceKind::Bishop => 'b',
+            PieceKind::Rook => 'r',
+            PieceKind::Queen => 'q',
+            PieceKind::King => 'k',
+        };
+        match self.get_color() {
+            Color::White => letter.to_ascii_uppercase(),
+            Color::Black => letter,
+        }
+    }
+}
+
+/// Creates a finished game piece of the given kind
+pub fn create_piece(kind: PieceKind, color: Color, position: (u8, u8)) -> Box<dyn Piece> {
+    match kind {
+        PieceKind::Pawn => Box::new(Pawn::new(color, position)),
+        PieceKind::Knight => Box::new(Knight::new(color, position)),
+        PieceKind::Bishop => Box::new(Bishop::new(color, position)),
+        PieceKind::Rook => Box::new(Rook::new(color, position)),
+        PieceKind::Queen => Box::new(Queen::new(color, position)),
+        PieceKind::King => Box::new(King::new(color, position)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::finished_game::color::Color;
+    use crate::finished_game::language::Language;
+    use crate::finished_game::piece::{create_piece, PieceKind};
+    use crate::square::BOARD_SIZE;
+
+    type Symmetry = fn((u8, u8)) -> (u8, u8);
+
+    /// Places a piece of `kind` on every square of an empty board and asserts that mirroring or
+    /// rotating the board gives the same moves as mirroring or rotating the square
+    fn assert_moves_symmetric(kind: PieceKind) {
+        let symmetries: [Symmetry; 4] = [
+            |(x, y)| (BOARD_SIZE - 1 - x, y),
+            |(x, y)| (x, BOARD_SIZE - 1 - y),
+            |(x, y)| (y, x),
+            |(x, y)| (BOARD_SIZE - 1 - y, x),
+        ];
+        let moves = |position: (u8, u8)| create_piece(kind, Color::White, position).get_moves(&HashSet::from([position]), &HashSet::new());
+        for position in (0..BOARD_SIZE).flat_map(|x| (0..BOARD_SIZE).map(move |y| (x, y))) {
+            for symmetry in symmetries {
+                let expected: HashSet<(u8, u8)> = moves(position).into_iter().map(symmetry).collect();
+                assert_eq!(moves(symmetry(position)), expected, "{:?} på {:?}", kind, position);
+            }
+        }
+    }
+
+    /// Places a piece of `kind` alone on every square of an empty board and asserts that it has
+    /// `expected_count` moves from there
+    fn exhaustive_piece_test(kind: PieceKind, expected_count: fn((u8, u8)) -> usize) {
+        for position in (0..BOARD_SIZE).flat_map(|x| (0..BOARD_SIZE).map(move |y| (x, y))) {
+            let moves = create_piece(kind, Color::White, position).get_moves(&HashSet::from([position]), &HashSet::new());
+            assert_eq!(moves.len(), expected_count(position), "{:?} på {:?}", kind, position);
+        }
+    }
+
+    #[test]
+    fn rook_has_14_moves_from_every_square() {
+        exhaustive_piece_test(PieceKind::Rook, |_| 14);
+    }
+
+    #[test]
+    fn bishop_has_more_moves_closer_to_the_centre() {
+        exhaustive_piece_test(PieceKind::Bishop, |(x, y)| {
+            let distance_to_edge = x.min(y).min(BOARD_SIZE - 1 - x).min(BOARD_SIZE - 1 - y);
+            7 + 2 * distance_to_edge as usize
+        });
+    }
+
+    #[test]
+    fn bishop_moves_are_symmetric() {
+        assert_moves_symmetric(PieceKind::Bishop);
+    }
+
+    #[test]
+    fn rook_moves_are_symmetric() {
+        assert_moves_symmetric(PieceKind::Rook);
+    }
+
+    #[test]
+    fn bishop_name_in_both_languages() {
+        let bishop = create_piece(PieceKind::Bishop, Color::White, (2, 0));
+        assert_eq!(bishop.name_in(Language::English), "bishop");
+        assert_eq!(bishop.name_in(Language::Norwegian), "laupar");
+    }
+
+    #[test]
+    fn white_knight_symbol() {
+        assert_eq!(create_piece(PieceKind::Knight, Color::White, (1, 0)).symbol(), 'N');
+    }
+
+    #[test]
+    fn black_queen_symbol() {
+        assert_eq!(create_piece(PieceKind::Queen, Color::Black, (3, 7)).symbol(), 'q');
+    }
 }
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
-use crate::square::{Square, Squares};
+use crate::finished_game::piece::{Piece, PieceKind};
+use crate::square::Square;
 
 #[derive(Clone)]
 pub struct King {
@@ -30,6 +30,10 @@ impl Piece for King {
         String::from(KING_NAME)
     }
 
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::King
+    }
+
     fn get_color(&self) -> Color {
         self.color
     }
@@ -43,12 +47,9 @@ impl Piece for King {
     }
 
     fn get_moves(&self, team: &HashSet<(u8, u8)>, _rival_team: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)> {
-        let (x, y) = self.position.as_i8().unwrap();
-        HashSet::from_iter([
-            (x - 1, y + 1), (x, y + 1), (x + 1, y + 1),
-            (x - 1, y    ),             (x + 1, y    ),
-            (x - 1, y - 1), (x, y - 1), (x + 1, y - 1),
-        ]).as_board_positions().difference(team).cloned().collect()
+        self.position.neighbors()
+            .filter(|square| !team.contains(square))
+            .collect()
     }
 }
 
@@ -75,4 +76,25 @@ mod tests {
         let legal_moves = set!["d4", "d5", "d6", "e6", "f6", "f5", "f4", "e4"];
         assert_eq_set!(king.get_moves(&empty_set!(), &empty_set!()), legal_moves)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_king_moves_corner() {
+        let king = King::new(Color::White, "a1".as_u8().unwrap());
+        let legal_moves = set!["a2", "b1", "b2"];
+        assert_eq_set!(king.get_moves(&empty_set!(), &empty_set!()), legal_moves)
+    }
+
+    #[test]
+    fn test_king_moves_all_neighbours() {
+        let king = King::new(Color::White, "e4".as_u8().unwrap());
+        let legal_moves = set!["d3", "d4", "d5", "e3", "e5", "f3", "f4", "f5"];
+        assert_eq_set!(king.get_moves(&empty_set!(), &empty_set!()), legal_moves)
+    }
+
+    #[test]
+    fn test_king_can_capture_rival_but_not_team() {
+        let king = King::new(Color::White, "a1".as_u8().unwrap());
+        let legal_moves = set!["b1", "b2"];
+        assert_eq_set!(king.get_moves(&set!["a1", "a2"], &set!["b2"]), legal_moves)
+    }
+}
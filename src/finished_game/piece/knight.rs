@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
-use crate::square::{Square, Squares};
+use crate::finished_game::piece::{Piece, PieceKind};
+use crate::square::{KNIGHT_OFFSETS, Square};
 
 #[derive(Clone)]
 pub struct Knight {
@@ -28,6 +28,9 @@ impl Piece for Knight {
     fn get_name(&self) -> String {
         String::from(KNIGHT_NAME)
     }
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Knight
+    }
     fn get_color(&self) -> Color {
         self.color
     }
@@ -39,13 +42,10 @@ impl Piece for Knight {
     }
     fn get_moves(&self, team: &HashSet<(u8, u8)>, _rival_team: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)> {
         let (x, y) = self.position.as_i8().unwrap();
-        HashSet::from_iter([
-                            (x - 1, y + 2), (x + 1, y + 2),
-            (x - 2, y + 1),                                 (x + 2, y + 1),
-
-            (x - 2, y - 1),                                 (x + 2, y - 1),
-                            (x - 1, y - 2), (x + 1, y - 2),
-        ]).as_board_positions().difference(team).cloned().collect()
+        KNIGHT_OFFSETS.into_iter()
+            .filter_map(|(dx, dy)| (x + dx, y + dy).as_u8())
+            .filter(|square| !team.contains(square))
+            .collect()
     }
 }
 
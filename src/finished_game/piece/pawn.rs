@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::square::{Square, Squares};
 
 #[derive(Clone)]
@@ -14,7 +14,8 @@ const PAWN_NAME: &str = "bonde";
 
 impl Pawn {
     pub fn get_pawn_moves(&self, other_pieces: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)> {
-        let (x, y) = self.position;
+        let (x, y) = self.position.as_i8().unwrap();
+        let other_pieces = other_pieces.iter().map(|&(x, y)| (x as i8, y as i8)).collect::<HashSet<_>>();
         match (self.color, y) {
             (Color::White, 1) if other_pieces.contains(&(x, y + 1)) => HashSet::new(),
             (Color::White, 1) => HashSet::from_iter([(x, 2), (x, 3)]),
@@ -22,7 +23,7 @@ impl Pawn {
             (Color::Black, 6) if other_pieces.contains(&(x, y - 1)) => HashSet::new(),
             (Color::Black, 6) => HashSet::from_iter([(x, 5), (x, 4)]),
             (Color::Black, _) => HashSet::from_iter([(x, y - 1)])
-        }.difference(other_pieces).cloned().collect()
+        }.difference(&other_pieces).cloned().collect::<HashSet<_>>().as_board_positions()
     }
 
     pub fn get_pawn_capture_moves(&self, rival_team: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)> {
@@ -50,6 +51,9 @@ impl Piece for Pawn {
     fn get_name(&self) -> String {
         String::from(PAWN_NAME)
     }
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Pawn
+    }
     fn get_color(&self) -> Color {
         self.color
     }
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
 use crate::finished_game::piece::bishop::Bishop;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::finished_game::piece::rook::Rook;
 use crate::square::MoveDirection;
 
@@ -32,6 +32,10 @@ impl Piece for Queen {
         String::from(QUEEN_NAME)
     }
 
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Queen
+    }
+
     fn get_color(&self) -> Color {
         self.color
     }
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
-use crate::square::MoveDirection;
+use crate::finished_game::piece::{Piece, PieceKind};
+use crate::square::{BOARD_SIZE, MoveDirection};
 
 #[derive(Clone)]
 pub struct Rook {
@@ -15,8 +15,8 @@ const ROOK_NAME: &str = "tårn";
 impl Rook {
     pub(crate) fn get_rook_moves(position: &(u8, u8)) -> HashSet<Vec<(u8, u8)>> {
         let (x, y) = *position;
-        let vertical: Vec<(u8, u8)> = vec![(x, 0), (x, 1), (x, 2), (x, 3), (x, 4), (x, 5), (x, 6), (x, 7)];
-        let horizontal: Vec<(u8, u8)> = vec![(0, y), (1, y), (2, y), (3, y), (4, y), (5, y), (6, y), (7, y)];
+        let vertical: Vec<(u8, u8)> = (0..BOARD_SIZE).map(|new_y| (x, new_y)).collect();
+        let horizontal: Vec<(u8, u8)> = (0..BOARD_SIZE).map(|new_x| (new_x, y)).collect();
 
         let north: Vec<(u8, u8)> = vertical.iter().cloned().filter(|&(_, new_y)| new_y > y).collect();
         let south: Vec<(u8, u8)> = vertical.iter().cloned().filter(|&(_, new_y)| new_y < y).rev().collect();
@@ -43,6 +43,9 @@ impl Piece for Rook {
     fn get_name(&self) -> String {
         String::from(ROOK_NAME)
     }
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Rook
+    }
     fn get_color(&self) -> Color {
         self.color
     }
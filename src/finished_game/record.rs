@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::finished_game::board::{Board, GameResult, Move};
+use crate::finished_game::color::Color;
+use crate::finished_game::pgn::{PgnError, read_movetext};
+
+/// A complete game: tag pairs like `("White", "Ola")`, every move played and the result
+#[derive(Debug, PartialEq)]
+pub struct GameRecord {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<Move>,
+    pub result: Option<GameResult>,
+    /// Notes on some of the moves, by the index of the move in `moves`
+    pub annotations: HashMap<usize, Annotation>,
+}
+
+/// A note written after a move: a NAG like `$1` for a good move or `$2` for a mistake, and a comment
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Annotation {
+    pub nag: Option<u8>,
+    pub comment: Option<String>,
+}
+
+fn result_token(result: Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::WhiteWins) => "1-0",
+        Some(GameResult::BlackWins) => "0-1",
+        Some(GameResult::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+impl GameRecord {
+    /// Returns the position the game starts from: the one in the FEN tag if there is one,
+    /// otherwise the normal start position
+    pub fn start_board(&self) -> Board {
+        self.tags.iter()
+            .find(|(key, _)| key == "FEN")
+            .and_then(|(_, fen)| Board::from_fen(fen).ok())
+            .unwrap_or_else(Board::new)
+    }
+
+    /// Returns the board after replaying every move from `start_board`
+    pub fn board(&self) -> Board {
+        let mut board = self.start_board();
+        for &mv in &self.moves {
+            if board.make_move(mv).is_err() {
+                break;
+            }
+        }
+        board
+    }
+
+    /// Plays the move written as `san` after the last move of the game
+    pub fn add_move(&mut self, san: &str) -> Result<(), PgnError> {
+        let board = self.board();
+        let mv = board.parse_san(san, board.get_active_color()).map_err(|_| PgnError::IllegalMove(san.to_string()))?;
+        self.moves.push(mv);
+        Ok(())
+    }
+
+    /// Plays the move written as `san`, with a comment written as `{comment}` after it
+    pub fn add_move_with_comment(&mut self, san: &str, comment: &str) -> Result<(), PgnError> {
+        self.add_move(san)?;
+        self.annotations.entry(self.moves.len() - 1).or_default().comment = Some(comment.to_string());
+        Ok(())
+    }
+
+    /// Plays the move written as `san`, marked with the NAG `$nag`, like `$1` for a good move
+    pub fn add_move_with_nag(&mut self, san: &str, nag: u8) -> Result<(), PgnError> {
+        self.add_move(san)?;
+        self.annotations.entry(self.moves.len() - 1).or_default().nag = Some(nag);
+        Ok(())
+    }
+
+    /// Returns the game as PGN, with one line per tag pair followed by the movetext and result
+    pub fn to_pgn(&self) -> String {
+        let mut pgn: String = self.tags.iter()
+            .map(|(key, value)| format!("[{} \"{}\"]\n", key, value))
+            .collect();
+        if !self.tags.is_empty() {
+            pgn.push('\n');
+        }
+        let mut board = self.start_board();
+        for (i, &mv) in self.moves.iter().enumerate() {
+            match board.get_active_color() {
+                Color::White => pgn.push_str(&format!("{}. ", board.fullmove_number())),
+                Color::Black if i == 0 => pgn.push_str(&format!("{}... ", board.fullmove_number())),
+                Color::Black => {}
+            }
+            let Ok(san) = board.to_san(mv) else { break };
+            pgn.push_str(&san);
+            pgn.push(' ');
+            if let Some(annotation) = self.annotations.get(&i) {
+                if let Some(nag) = annotation.nag {
+                    pgn.push_str(&format!("${} ", nag));
+                }
+                if let Some(comment) = &annotation.comment {
+                    pgn.push_str(&format!("{{{}}} ", comment));
+                }
+            }
+            if board.make_move(mv).is_err() {
+                break;
+            }
+        }
+        pgn.push_str(result_token(self.result));
+        pgn.push('\n');
+        pgn
+    }
+
+    /// Reads a game written by `to_pgn`. Comments and NAGs are skipped.
+    pub fn from_pgn(pgn: &str) -> Result<GameRecord, PgnError> {
+        let tags = pgn.lines()
+            .filter_map(|line| line.trim().strip_prefix('[')?.strip_suffix(']'))
+            .filter_map(|tag| {
+                let (key, value) = tag.split_once(' ')?;
+                Some((key.to_string(), value.trim_matches('"').to_string()))
+            })
+            .collect();
+        let result = [GameResult::WhiteWins, GameResult::BlackWins, GameResult::Draw].into_iter()
+            .find(|&result| pgn.split_whitespace().any(|token| token == result_token(Some(result))));
+        Ok(GameRecord { tags, moves: read_movetext(pgn)?, result, annotations: HashMap::new() })
+    }
+
+    /// Writes the game as PGN to the file at `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_pgn())
+    }
+
+    /// Reads a game saved with `save` from the file at `path`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<GameRecord> {
+        let pgn = fs::read_to_string(path)?;
+        GameRecord::from_pgn(&pgn).map_err(|error| match error {
+            PgnError::IllegalMove(san) => io::Error::new(io::ErrorKind::InvalidData, format!("Ulovleg trekk i fila: {}", san)),
+            PgnError::InvalidFen(fen) => io::Error::new(io::ErrorKind::InvalidData, format!("Ugyldig stilling i fila: {}", fen)),
+            PgnError::Io(kind) => io::Error::from(kind),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::env;
+    use std::fs;
+
+    use crate::finished_game::board::{Board, GameResult};
+    use crate::finished_game::pgn::PgnError;
+    use crate::finished_game::record::GameRecord;
+
+    fn scholars_mate() -> GameRecord {
+        let mut board = Board::new();
+        let moves = ["e2e4", "e7e5", "d1h5", "b8c6", "f1c4", "g8f6", "h5f7"]
+            .iter()
+            .map(|uci| {
+                let mv = board.move_from_uci(uci).unwrap();
+                board.make_move(mv).unwrap();
+                mv
+            })
+            .collect();
+        GameRecord {
+            tags: vec![(String::from("White"), String::from("Ola")), (String::from("Black"), String::from("Kari"))],
+            moves,
+            result: Some(GameResult::WhiteWins),
+            annotations: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn to_pgn_writes_tags_and_san() {
+        assert_eq!(
+            scholars_mate().to_pgn(),
+            "[White \"Ola\"]\n[Black \"Kari\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0\n"
+        );
+    }
+
+    #[test]
+    fn commented_move_is_written_after_the_move() {
+        let mut record = GameRecord { tags: Vec::new(), moves: Vec::new(), result: None, annotations: HashMap::new() };
+        record.add_move_with_comment("e4", "good").unwrap();
+        record.add_move_with_nag("f6", 2).unwrap();
+        record.add_move("Qh5+").unwrap();
+        assert_eq!(record.add_move("Ke7"), Err(PgnError::IllegalMove(String::from("Ke7"))));
+
+        let pgn = record.to_pgn();
+        assert_eq!(pgn, "1. e4 {good} f6 $2 2. Qh5+ *\n");
+        assert_eq!(GameRecord::from_pgn(&pgn).unwrap().moves, record.moves);
+    }
+
+    #[test]
+    fn saved_game_is_reloaded_with_same_history() {
+        let record = scholars_mate();
+        let path = env::temp_dir().join("rust_chess_workshop_record_test.pgn");
+        record.save(&path).unwrap();
+        let loaded = GameRecord::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, record);
+        assert_eq!(loaded.board().diff(&record.board()), vec![]);
+        assert_eq!(loaded.board().result(), Some(GameResult::WhiteWins));
+    }
+
+    #[test]
+    fn game_from_fen_tag_keeps_move_numbers() {
+        let pgn = "[FEN \"6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 4 30\"]\n\n30... h6 31. Ra8+ Kh7 *\n";
+        let record = GameRecord::from_pgn(pgn).unwrap();
+        assert_eq!(record.to_pgn(), pgn);
+        assert_eq!(record.board().to_fen(), "R7/5ppk/7p/8/8/8/5PPP/6K1 w - - 2 32");
+    }
+}
@@ -0,0 +1,24 @@
+/// Rules that can be switched on or off to play chess variants on the same board
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ruleset {
+    /// Whether castling is allowed
+    pub castling: bool,
+    /// Whether a capture must be made when one is available, as in antichess
+    pub mandatory_captures: bool,
+}
+
+impl Ruleset {
+    pub fn standard() -> Ruleset {
+        Ruleset { castling: true, mandatory_captures: false }
+    }
+
+    pub fn antichess() -> Ruleset {
+        Ruleset { castling: false, mandatory_captures: true }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset::standard()
+    }
+}
@@ -1,6 +1,9 @@
 use std::env::args;
+use std::fs;
+use std::time::Duration;
 
-use crate::test_runner::highest_passing_test;
+use crate::finished_game::color::Color;
+use crate::test_runner::{failing_scenarios, highest_passing_test, run_scenario};
 
 mod assertions;
 mod finished_game;
@@ -32,6 +35,46 @@ fn main() {
             "7" => task_7::main(),
             "8" => task_8::main(),
             "9" => task_9::main(),
+            "motor" => {
+                let difficulty = args().nth(2).and_then(|difficulty| difficulty.parse().ok()).unwrap_or(3);
+                finished_game::play_vs_engine(Color::White, difficulty);
+                return;
+            }
+            "960" => {
+                finished_game::play_960();
+                return;
+            }
+            "perft" => {
+                let depth = args().nth(2).and_then(|depth| depth.parse().ok()).unwrap_or(3);
+                finished_game::print_perft_divide(depth);
+                return;
+            }
+            "pgn" => {
+                let delay = args().nth(3).and_then(|millis| millis.parse().ok()).map_or(Duration::from_secs(1), Duration::from_millis);
+                match args().nth(2).map(|path| fs::read_to_string(&path).map_err(|error| format!("Kunne ikkje lese {}: {}", path, error))) {
+                    Some(Ok(movetext)) => if let Err(error) = finished_game::watch_pgn(&movetext, delay) {
+                        println!("{}", error.message());
+                    },
+                    Some(Err(message)) => println!("{}", message),
+                    None => println!("Bruk: cargo run pgn <fil> [millisekund mellom trekka]"),
+                }
+                return;
+            }
+            "analyse" => {
+                match args().nth(2) {
+                    Some(fen) => finished_game::print_analysis(&fen),
+                    None => println!("Bruk: cargo run analyse \"<FEN>\""),
+                }
+                return;
+            }
+            "scenario" => {
+                match args().nth(2).map(|name| run_scenario(&name)) {
+                    Some(Ok(())) => println!("Scenarioet gjekk gjennom"),
+                    Some(Err(message)) => println!("{}", message),
+                    None => println!("Bruk: cargo run scenario <namn>"),
+                }
+                return;
+            }
             _ => {
                 println!("Køyrer ferdig spel");
                 finished_game::main();
@@ -41,6 +84,9 @@ fn main() {
 
     if let Some(task) = highest_passing_test() {
         println!("Tester til og med oppgåve {} køyrde grønt!", task);
+        for problem in failing_scenarios(task + 1) {
+            println!("Scenario for oppgåve {} feila: {}", task + 1, problem);
+        }
         match task {
             0 => task_1::main(),
             _ => {
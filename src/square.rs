@@ -1,5 +1,28 @@
 use std::collections::HashSet;
 
+/// Antall ruter langs hver side av brettet
+pub const BOARD_SIZE: u8 = 8;
+
+/// De 8 hoppene en springer kan gjøre, som `(dx, dy)`
+pub const KNIGHT_OFFSETS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+
+/// De 8 stegene en konge kan ta, som `(dx, dy)`
+pub const KING_OFFSETS: [(i8, i8); 8] = [(-1, 1), (0, 1), (1, 1), (-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)];
+
+/// Returnerer de 32 lyse rutene, fra a1-hjørnet og bortover hver rad
+pub fn light_squares() -> Vec<(u8, u8)> {
+    all_squares().filter(|square| square.is_light()).collect()
+}
+
+/// Returnerer de 32 mørke rutene, fra a1 og bortover hver rad
+pub fn dark_squares() -> Vec<(u8, u8)> {
+    all_squares().filter(|square| !square.is_light()).collect()
+}
+
+fn all_squares() -> impl Iterator<Item=(u8, u8)> {
+    (0..BOARD_SIZE).flat_map(|y| (0..BOARD_SIZE).map(move |x| (x, y)))
+}
+
 pub trait Squares {
     fn as_board_positions(&self) -> HashSet<(u8, u8)>;
 }
@@ -7,9 +30,8 @@ pub trait Squares {
 impl Squares for HashSet<(i8, i8)> {
     /// Returnerer et nytt `HashSet` med posisjonene som er innenfor brettet (filterer ut negative og >= 8)
     fn as_board_positions(&self) -> HashSet<(u8, u8)> {
-        self.iter().cloned()
-            .filter(|(x, y)| (0..8).contains(x) && (0..8).contains(y))
-            .map(|(x, y)| (x as u8, y as u8))
+        self.iter()
+            .filter_map(|square| square.as_u8())
             .collect()
     }
 }
@@ -18,7 +40,7 @@ impl Squares for HashSet<(u8, u8)> {
     /// Returnerer et nytt `HashSet` med posisjonene som er innenfor brettet (filterer ut negative og >= 8)
     fn as_board_positions(&self) -> HashSet<(u8, u8)> {
         self.iter().cloned()
-            .filter(|(x, y)| (0..8).contains(x) && (0..8).contains(y))
+            .filter(Square::on_board)
             .collect()
     }
 }
@@ -36,6 +58,73 @@ pub trait Square {
     fn as_i8(&self) -> Option<(i8, i8)>;
     fn as_u8(&self) -> Option<(u8, u8)>;
     fn as_string(&self) -> String;
+
+    /// Returnerer true når ruta er innenfor brettet
+    fn on_board(&self) -> bool {
+        let size = BOARD_SIZE as i8;
+        self.as_i8().is_some_and(|(x, y)| (0..size).contains(&x) && (0..size).contains(&y))
+    }
+
+    /// Returnerer true når ruta er lys. a1 er mørk.
+    ///
+    /// Får panikk når ruta ikke kan leses, som `"z9"` eller `""`
+    fn is_light(&self) -> bool {
+        let (x, y) = self.as_i8().unwrap();
+        (x + y) % 2 == 1
+    }
+
+    /// Returnerer de opptil 8 rutene rundt denne ruta som er innenfor brettet
+    fn neighbors(&self) -> impl Iterator<Item=(u8, u8)> where Self: Sized {
+        let (x, y) = self.as_i8().unwrap();
+        KING_OFFSETS.into_iter()
+            .filter_map(move |(dx, dy)| (x + dx, y + dy).as_u8())
+    }
+
+    /// Returnerer rutene mellom denne ruta og `other`, i rekkefølge, når de står på samme rad, linje
+    /// eller diagonal. Ellers returneres en tom liste.
+    fn squares_between(&self, other: &Self) -> Vec<(u8, u8)> where Self: Sized {
+        let (x, y) = self.as_i8().unwrap();
+        let (other_x, other_y) = other.as_i8().unwrap();
+        let (dx, dy) = (other_x - x, other_y - y);
+        if !(dx == 0 || dy == 0 || dx.abs() == dy.abs()) {
+            return Vec::new();
+        }
+        (1..dx.abs().max(dy.abs()))
+            .map(|step| ((x + dx.signum() * step) as u8, (y + dy.signum() * step) as u8))
+            .collect()
+    }
+
+    /// Returnerer minste antall springerhopp fra denne ruta til `other`, eller `None` når en av rutene
+    /// er utenfor brettet
+    fn knight_distance(&self, other: &Self) -> Option<u8> where Self: Sized {
+        if !self.on_board() || !other.on_board() {
+            return None
+        }
+        let target = other.as_i8()?;
+        let mut visited = HashSet::from([self.as_i8()?]);
+        let mut frontier = visited.clone();
+        let mut distance = 0;
+        while !frontier.contains(&target) {
+            frontier = frontier.iter()
+                .flat_map(|&(x, y)| KNIGHT_OFFSETS.map(|(dx, dy)| (x + dx, y + dy)))
+                .filter(|square| square.on_board() && !visited.contains(square))
+                .collect();
+            visited.extend(&frontier);
+            distance += 1;
+        }
+        Some(distance)
+    }
+
+    /// Returnerer antall steg langs rader og linjer fra denne ruta til `other`, eller `None` når en av
+    /// rutene er utenfor brettet
+    fn manhattan_distance(&self, other: &Self) -> Option<u8> where Self: Sized {
+        if !self.on_board() || !other.on_board() {
+            return None
+        }
+        let (x, y) = self.as_u8()?;
+        let (other_x, other_y) = other.as_u8()?;
+        Some(x.abs_diff(other_x) + y.abs_diff(other_y))
+    }
 }
 
 impl Square for (u8, u8) {
@@ -54,6 +143,21 @@ impl Square for (u8, u8) {
      }
 }
 
+impl Square for (i8, i8) {
+    fn as_i8(&self) -> Option<(i8, i8)> {
+        Some(*self)
+    }
+
+    /// Returnerer `None` når ruta er utenfor brettet
+    fn as_u8(&self) -> Option<(u8, u8)> {
+        self.on_board().then_some((self.0 as u8, self.1 as u8))
+    }
+
+    fn as_string(&self) -> String {
+        self.as_u8().map(|square| square.as_string()).unwrap_or_default()
+    }
+}
+
 impl Square for &str {
     fn as_i8(&self) -> Option<(i8, i8)> {
         self.as_u8().map(|coordinate| (coordinate.0 as i8, coordinate.1 as i8))
@@ -65,10 +169,7 @@ impl Square for &str {
         let file = chars.next().unwrap().to_ascii_lowercase() as i8 - 97;
         let rank = chars.next().unwrap() as i8 - 49;
 
-        if (0..8).contains(&file) && (0..8).contains(&rank) {
-            return Some((file as u8, rank as u8));
-        }
-        None
+        (file, rank).as_u8()
     }
 
     fn as_string(&self) -> String {
@@ -117,3 +218,76 @@ macro_rules! set {
         vec![$($x),*].as_board_positions()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::square::{dark_squares, light_squares, KNIGHT_OFFSETS, Square};
+
+    #[test]
+    fn squares_between_on_rank_and_diagonal() {
+        assert_eq!("e1".squares_between(&"h1"), vec![(5, 0), (6, 0)]);
+        assert_eq!("a1".squares_between(&"d4"), vec![(1, 1), (2, 2)]);
+        assert_eq!("a1".squares_between(&"b3"), vec![]);
+        assert_eq!("a1".squares_between(&"a2"), vec![]);
+    }
+
+    #[test]
+    fn knight_distance_to_neighbour_jump() {
+        assert_eq!(Square::knight_distance(&"a1", &"b3"), Some(1));
+        assert_eq!(Square::knight_distance(&(0_u8, 0_u8), &(0_u8, 0_u8)), Some(0));
+    }
+
+    #[test]
+    fn distances_to_squares_outside_the_board_are_none() {
+        assert_eq!((0_i8, 0_i8).knight_distance(&(8_i8, 3_i8)), None);
+        assert_eq!((-1_i8, 0_i8).knight_distance(&(1_i8, 1_i8)), None);
+        assert_eq!("a1".manhattan_distance(&"i9"), None);
+    }
+
+    #[test]
+    fn manhattan_distance_counts_rank_and_file_steps() {
+        assert_eq!("a1".manhattan_distance(&"h8"), Some(14));
+        assert_eq!("e4".manhattan_distance(&"c5"), Some(3));
+        assert_eq!("d4".manhattan_distance(&"d4"), Some(0));
+    }
+
+    #[test]
+    fn on_board_only_inside_the_edges() {
+        assert!((7_u8, 7_u8).on_board());
+        assert!(!(8_u8, 0_u8).on_board());
+        assert!(!(-1_i8, 3_i8).on_board());
+        assert_eq!((3_i8, 8_i8).as_u8(), None);
+    }
+
+    #[test]
+    fn corner_has_three_neighbors() {
+        assert_eq!("a1".neighbors().count(), 3);
+        assert_eq!("d4".neighbors().count(), 8);
+        assert!("h8".neighbors().all(|square| ["g8", "g7", "h7"].contains(&square.as_string().as_str())));
+    }
+
+    #[test]
+    fn knight_offsets_are_all_jumps() {
+        assert_eq!(KNIGHT_OFFSETS.len(), 8);
+        assert!(KNIGHT_OFFSETS.iter().all(|(dx, dy)| dx.abs() + dy.abs() == 3 && *dx != 0 && *dy != 0));
+    }
+
+    #[test]
+    fn light_and_dark_squares_cover_the_board() {
+        let light: HashSet<(u8, u8)> = light_squares().into_iter().collect();
+        let dark: HashSet<(u8, u8)> = dark_squares().into_iter().collect();
+        assert_eq!(light.len(), 32);
+        assert_eq!(dark.len(), 32);
+        assert!(light.is_disjoint(&dark));
+        assert_eq!(light.union(&dark).count(), 64);
+        assert!(dark.contains(&"a1".as_u8().unwrap()) && light.contains(&"h1".as_u8().unwrap()));
+    }
+
+    #[test]
+    fn knight_distance_across_board() {
+        assert_eq!("a1".knight_distance(&"h8"), Some(6));
+        assert_eq!("a1".knight_distance(&"b2"), Some(4));
+    }
+}
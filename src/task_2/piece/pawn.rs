@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 
 /// # Oppgave 2
 ///
@@ -64,6 +64,10 @@ impl Piece for Pawn {
         String::from(PAWN_NAME)
     }
 
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Pawn
+    }
+
     fn get_color(&self) -> Color {
         self.color
     }
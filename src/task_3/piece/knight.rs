@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 
 /// # Oppgave 3
 ///
@@ -50,6 +50,10 @@ impl Piece for Knight {
         String::from(KNIGHT_NAME)
     }
 
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Knight
+    }
+
     fn get_color(&self) -> Color {
         self.color
     }
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::square::{Square, Squares};
 
 #[derive(Clone)]
@@ -31,6 +31,10 @@ impl Piece for King {
         String::from(KING_NAME)
     }
 
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::King
+    }
+
     fn get_color(&self) -> Color {
         self.color
     }
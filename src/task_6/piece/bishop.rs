@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::square::{MoveDirection, Square};
 
 /// # Oppgave 6
@@ -113,6 +113,10 @@ impl Piece for Bishop {
         String::from(BISHOP_NAME)
     }
 
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Bishop
+    }
+
     fn get_color(&self) -> Color {
         self.color
     }
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::square::MoveDirection;
 
 #[derive(Clone)]
@@ -28,6 +28,9 @@ impl Piece for Rook {
     fn get_name(&self) -> String {
         String::from(ROOK_NAME)
     }
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Rook
+    }
     fn get_color(&self) -> Color {
         self.color
     }
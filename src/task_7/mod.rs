@@ -1,4 +1,4 @@
-mod piece;
+pub(crate) mod piece;
 mod game;
 mod board;
 
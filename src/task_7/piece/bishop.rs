@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::square::{MoveDirection, Square};
 
 #[derive(Clone)]
@@ -74,6 +74,9 @@ impl Piece for Bishop {
     fn get_name(&self) -> String {
         String::from(BISHOP_NAME)
     }
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Bishop
+    }
     fn get_color(&self) -> Color {
         self.color
     }
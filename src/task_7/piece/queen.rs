@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::finished_game::color::Color;
-use crate::finished_game::piece::Piece;
+use crate::finished_game::piece::{Piece, PieceKind};
 use crate::square::MoveDirection;
 
 /// # Oppgave 7
@@ -37,6 +37,9 @@ impl Piece for Queen {
     fn get_name(&self) -> String {
         String::from(QUEEN_NAME)
     }
+    fn get_kind(&self) -> PieceKind {
+        PieceKind::Queen
+    }
     fn get_color(&self) -> Color {
         self.color
     }
@@ -56,8 +59,10 @@ mod tests {
     use std::collections::HashSet;
 
     use crate::{assert_eq_set, empty_set, set};
+    use crate::finished_game::board::Board;
     use crate::finished_game::color::Color;
     use crate::finished_game::piece::Piece;
+    use crate::finished_game::piece::queen::Queen as FinishedQueen;
     use crate::square::{Square, Squares};
     use crate::task_7::piece::queen::Queen;
 
@@ -87,4 +92,12 @@ mod tests {
         ];
         assert_eq_set!(queen.get_moves(&empty_set!(), &empty_set!()), legal_moves)
     }
+
+    #[test]
+    fn queen_can_be_placed_on_finished_board() {
+        let board = Board::from_pieces(vec![Box::new(Queen::new(Color::White, "d1".as_u8().unwrap()))]);
+        let finished_board = Board::from_pieces(vec![Box::new(FinishedQueen::new(Color::White, "d1".as_u8().unwrap()))]);
+        assert_eq!(board.get_square_color(&"d1".as_u8().unwrap()), Some(Color::White));
+        assert_eq!(board.diff(&finished_board), vec![])
+    }
 }
@@ -1,5 +1,13 @@
+use std::collections::HashSet;
+use std::panic;
 use std::process::Command;
 
+use crate::finished_game::board::Board;
+use crate::finished_game::color::Color;
+use crate::finished_game::piece::Piece;
+use crate::square::{Square, Squares};
+use crate::{task_1, task_2, task_3, task_4, task_5, task_6, task_7};
+
 pub fn all_tests_pass() -> bool {
     let output = Command::new("cargo")
         .arg("test")
@@ -30,4 +38,146 @@ pub fn run_tests_for_task(task: u8) -> bool {
         .expect("Failed to execute 'cargo test'");
 
     output.status.success()
-}
\ No newline at end of file
+}
+
+/// Trekka til ei brikke av fargen `color` på `position`, når dei eigne brikkene står på `team` og
+/// motstandaren sine på `rival_team`
+pub type MoveFn = fn(Color, (u8, u8), &HashSet<(u8, u8)>, &HashSet<(u8, u8)>) -> HashSet<(u8, u8)>;
+
+/// Ei stilling der brikka på `square` skal kunne flytte til nøyaktig rutene i `expected`, køyrd mot
+/// brikka frå oppgåve `task`
+pub struct Scenario {
+    pub name: &'static str,
+    pub task: u8,
+    pub fen: &'static str,
+    pub square: &'static str,
+    pub expected: &'static [&'static str],
+}
+
+pub const SCENARIOS: [Scenario; 7] = [
+    Scenario { name: "bonde_fra_start", task: 1, fen: "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", square: "e2", expected: &["e3", "e4"] },
+    Scenario { name: "bonde_slar_pa_skra", task: 2, fen: "4k3/8/8/3p1p2/4P3/8/8/4K3 w - - 0 1", square: "e4", expected: &["d5", "e5", "f5"] },
+    Scenario { name: "springar_i_hjornet", task: 3, fen: "7k/8/8/8/8/8/8/N6K w - - 0 1", square: "a1", expected: &["b3", "c2"] },
+    Scenario { name: "konge_i_hjornet", task: 4, fen: "7k/8/8/8/8/8/8/K7 w - - 0 1", square: "a1", expected: &["a2", "b1", "b2"] },
+    Scenario { name: "blokkert_tarn", task: 5, fen: "7k/8/8/8/R2p4/8/P7/7K w - - 0 1", square: "a4", expected: &["a3", "a5", "a6", "a7", "a8", "b4", "c4", "d4"] },
+    Scenario { name: "blokkert_loper", task: 6, fen: "7k/8/8/8/8/2p5/1B6/K7 w - - 0 1", square: "b2", expected: &["a3", "c1", "c3"] },
+    Scenario { name: "dronning_i_hjornet", task: 7, fen: "7k/8/8/8/8/8/1P6/QR5K w - - 0 1", square: "a1", expected: &["a2", "a3", "a4", "a5", "a6", "a7", "a8"] },
+];
+
+/// Køyrer scenarioet med namnet `name` mot brikka frå oppgåva det høyrer til. Feilmeldinga seier kva
+/// ruter som manglar og kva ruter som er med utan å vere lovlege.
+pub fn run_scenario(name: &str) -> Result<(), String> {
+    let scenario = SCENARIOS.iter()
+        .find(|scenario| scenario.name == name)
+        .ok_or_else(|| format!("Fann ikkje scenarioet {}", name))?;
+    check_scenario(scenario, task_moves(scenario.task))
+}
+
+/// Køyrer scenarioa for oppgåve `task` og returnerer feilmeldingane til dei som ikkje gjekk gjennom
+pub fn failing_scenarios(task: u8) -> Vec<String> {
+    SCENARIOS.iter()
+        .filter(|scenario| scenario.task == task)
+        .filter_map(|scenario| check_scenario(scenario, task_moves(task)).err())
+        .collect()
+}
+
+/// Trekka til brikka deltakaren implementerer i oppgåve `task`
+fn task_moves(task: u8) -> MoveFn {
+    match task {
+        1 => task_1_pawn_moves,
+        2 => piece_moves::<task_2::piece::pawn::Pawn>,
+        3 => piece_moves::<task_3::piece::knight::Knight>,
+        4 => piece_moves::<task_4::piece::king::King>,
+        5 => piece_moves::<task_5::piece::rook::Rook>,
+        6 => piece_moves::<task_6::piece::bishop::Bishop>,
+        _ => piece_moves::<task_7::piece::queen::Queen>,
+    }
+}
+
+/// Trekka til bonden frå oppgåve 1, som har sin eigen `Piece`-trait
+fn task_1_pawn_moves(color: Color, position: (u8, u8), team: &HashSet<(u8, u8)>, rival_team: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)> {
+    use crate::task_1::piece::Piece;
+    task_1::piece::pawn::Pawn::new(color, position).get_moves(team, rival_team)
+}
+
+fn piece_moves<P: Piece>(color: Color, position: (u8, u8), team: &HashSet<(u8, u8)>, rival_team: &HashSet<(u8, u8)>) -> HashSet<(u8, u8)> {
+    P::new(color, position).get_moves(team, rival_team)
+}
+
+fn check_scenario(scenario: &Scenario, moves: MoveFn) -> Result<(), String> {
+    let board = Board::from_fen(scenario.fen).map_err(|_| format!("{}: ugyldig FEN {}", scenario.name, scenario.fen))?;
+    let position = scenario.square.as_u8().unwrap();
+    let color = board.color_at(position).ok_or_else(|| format!("{}: inga brikke på {}", scenario.name, scenario.square))?;
+    let (team, rival_team) = (board.get_positions(color), board.get_positions(color.opposite()));
+    let actual = panic::catch_unwind(|| moves(color, position, &team, &rival_team))
+        .map_err(|_| format!("{}: brikka på {} fekk panikk, er todo!() fjerna?", scenario.name, scenario.square))?;
+    let expected = scenario.expected.as_board_positions();
+
+    let missing: HashSet<(u8, u8)> = expected.difference(&actual).cloned().collect();
+    let extra: HashSet<(u8, u8)> = actual.difference(&expected).cloned().collect();
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("manglar {}", squares_list(&missing)));
+    }
+    if !extra.is_empty() {
+        problems.push(format!("kan ulovleg flytte til {}", squares_list(&extra)));
+    }
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(format!("{} ({}): brikka på {} {}", scenario.name, scenario.fen, scenario.square, problems.join(" og "))),
+    }
+}
+
+/// Returnerer rutene sorterte og skrivne som `"d6, e6"`
+fn squares_list(squares: &HashSet<(u8, u8)>) -> String {
+    let mut squares: Vec<&(u8, u8)> = squares.iter().collect();
+    squares.sort_by_key(|(x, y)| (*y, *x));
+    squares.iter().map(|square| square.as_string()).collect::<Vec<String>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::finished_game::piece::bishop::Bishop;
+    use crate::finished_game::piece::king::King;
+    use crate::finished_game::piece::knight::Knight;
+    use crate::finished_game::piece::pawn::Pawn;
+    use crate::finished_game::piece::queen::Queen;
+    use crate::finished_game::piece::rook::Rook;
+    use crate::test_runner::{check_scenario, MoveFn, piece_moves, run_scenario, Scenario, SCENARIOS};
+
+    /// Trekka til brikka frå det ferdige spelet som svarar til oppgåve `task`
+    fn reference_moves(task: u8) -> MoveFn {
+        match task {
+            1 | 2 => piece_moves::<Pawn>,
+            3 => piece_moves::<Knight>,
+            4 => piece_moves::<King>,
+            5 => piece_moves::<Rook>,
+            6 => piece_moves::<Bishop>,
+            _ => piece_moves::<Queen>,
+        }
+    }
+
+    #[test]
+    fn reference_pieces_pass_every_scenario() {
+        for scenario in &SCENARIOS {
+            assert_eq!(check_scenario(scenario, reference_moves(scenario.task)), Ok(()));
+        }
+        assert_eq!(run_scenario("finst_ikkje"), Err(String::from("Fann ikkje scenarioet finst_ikkje")));
+    }
+
+    #[test]
+    fn wrong_expected_squares_are_reported() {
+        let scenario = Scenario { name: "feil", task: 3, fen: "7k/8/8/8/8/8/8/N6K w - - 0 1", square: "a1", expected: &["b3", "b4"] };
+        assert_eq!(
+            check_scenario(&scenario, reference_moves(scenario.task)),
+            Err(String::from("feil (7k/8/8/8/8/8/8/N6K w - - 0 1): brikka på a1 manglar b4 og kan ulovleg flytte til c2"))
+        );
+    }
+
+    #[test]
+    fn unfinished_task_is_reported() {
+        let scenario = &SCENARIOS[2];
+        let todo: MoveFn = |_, _, _, _| todo!();
+        assert_eq!(check_scenario(scenario, todo), Err(String::from("springar_i_hjornet: brikka på a1 fekk panikk, er todo!() fjerna?")));
+    }
+}